@@ -3361,6 +3361,134 @@ pub async fn delete_agent_kv_key(
     }
 }
 
+/// GET /api/memory/export — Export memory (KV, semantic memories, knowledge graph,
+/// sessions) as a portable snapshot.
+///
+/// Query params: `format` (`json` | `msgpack`, default `json`), `agent` (name or ID,
+/// optional — the knowledge graph is always exported in full), `sessions`
+/// (`true` | `false`, default `true`).
+pub async fn export_memory(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> axum::response::Response {
+    use openfang_types::memory::ExportFormat;
+
+    let format_str = params.get("format").map(|s| s.as_str()).unwrap_or("json");
+    let Some(format) = ExportFormat::parse(format_str) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": format!("Unknown format '{format_str}' (expected json or msgpack)")})),
+        )
+            .into_response();
+    };
+
+    let agent_id = match params.get("agent") {
+        Some(agent_ref) => match agent_ref.parse::<AgentId>() {
+            Ok(id) => Some(id),
+            Err(_) => match state.kernel.registry.find_by_name(agent_ref) {
+                Some(entry) => Some(entry.id),
+                None => {
+                    return (
+                        StatusCode::NOT_FOUND,
+                        Json(serde_json::json!({"error": "Agent not found"})),
+                    )
+                        .into_response();
+                }
+            },
+        },
+        None => None,
+    };
+    let include_sessions = params
+        .get("sessions")
+        .map(|v| v != "false" && v != "0")
+        .unwrap_or(true);
+
+    let options = openfang_memory::portability::ExportOptions {
+        agent_id,
+        include_sessions,
+    };
+    match state.kernel.memory.export_with(options, format).await {
+        Ok(data) => {
+            let filename = format!("openfang-memory.{}", format.extension());
+            (
+                StatusCode::OK,
+                [
+                    (
+                        axum::http::header::CONTENT_TYPE,
+                        format.content_type().to_string(),
+                    ),
+                    (
+                        axum::http::header::CONTENT_DISPOSITION,
+                        format!("attachment; filename=\"{filename}\""),
+                    ),
+                ],
+                data,
+            )
+                .into_response()
+        }
+        Err(e) => {
+            tracing::warn!("Memory export failed: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Memory export failed: {e}")})),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// POST /api/memory/import — Import a snapshot produced by `/api/memory/export`.
+///
+/// The request body is the raw snapshot. Query params: `format` (`json` | `msgpack`,
+/// default `json`), `mode` (`merge` | `replace`, default `merge`). Returns an
+/// `ImportReport` with per-record errors.
+pub async fn import_memory(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+    body: axum::body::Bytes,
+) -> impl IntoResponse {
+    use openfang_types::memory::{ExportFormat, ImportMode};
+
+    let format_str = params.get("format").map(|s| s.as_str()).unwrap_or("json");
+    let Some(format) = ExportFormat::parse(format_str) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(
+                serde_json::json!({"error": format!("Unknown format '{format_str}' (expected json or msgpack)")}),
+            ),
+        );
+    };
+    let mode_str = params.get("mode").map(|s| s.as_str()).unwrap_or("merge");
+    let Some(mode) = ImportMode::parse(mode_str) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(
+                serde_json::json!({"error": format!("Unknown mode '{mode_str}' (expected merge or replace)")}),
+            ),
+        );
+    };
+    if body.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Empty request body"})),
+        );
+    }
+
+    match state.kernel.memory.import_with(&body, format, mode).await {
+        Ok(report) => (
+            StatusCode::OK,
+            Json(serde_json::to_value(&report).unwrap_or_default()),
+        ),
+        Err(e) => {
+            tracing::warn!("Memory import failed: {e}");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": format!("Memory import failed: {e}")})),
+            )
+        }
+    }
+}
+
 /// GET /api/health — Minimal liveness probe (public, no auth required).
 /// Returns only status and version to prevent information leakage.
 /// Use GET /api/health/detail for full diagnostics (requires auth).
@@ -5609,6 +5737,103 @@ pub async fn list_sessions(State(state): State<Arc<AppState>>) -> impl IntoRespo
     }
 }
 
+/// GET /api/sessions/search — Full-text search over session transcripts.
+///
+/// Query params: `q` (required), `agent` (name or ID), `session`, `role`
+/// (`user` | `assistant` | `system`), `after` / `before` (RFC 3339 or
+/// `YYYY-MM-DD`), `limit` (default 20, max 200), `offset`. Matched words in
+/// each `snippet` are wrapped in `<mark>`…`</mark>`.
+pub async fn search_sessions(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    use openfang_memory::session::SessionSearchQuery;
+    use openfang_types::message::Role;
+
+    let bad_request = |msg: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": msg})),
+        )
+    };
+    let Some(text) = params.get("q").filter(|q| !q.trim().is_empty()) else {
+        return bad_request("Missing 'q' parameter".to_string());
+    };
+    let mut query = SessionSearchQuery::new(text.clone());
+
+    if let Some(agent_ref) = params.get("agent") {
+        query.agent_id = match agent_ref.parse::<AgentId>() {
+            Ok(id) => Some(id),
+            Err(_) => match state.kernel.registry.find_by_name(agent_ref) {
+                Some(entry) => Some(entry.id),
+                None => {
+                    return (
+                        StatusCode::NOT_FOUND,
+                        Json(serde_json::json!({"error": "Agent not found"})),
+                    );
+                }
+            },
+        };
+    }
+    if let Some(session) = params.get("session") {
+        match session.parse::<uuid::Uuid>() {
+            Ok(u) => query.session_id = Some(openfang_types::agent::SessionId(u)),
+            Err(_) => return bad_request("Invalid session ID".to_string()),
+        }
+    }
+    query.role = match params.get("role").map(|r| r.as_str()) {
+        None => None,
+        Some("user") => Some(Role::User),
+        Some("assistant") => Some(Role::Assistant),
+        Some("system") => Some(Role::System),
+        Some(other) => return bad_request(format!("Invalid role '{other}'")),
+    };
+    for (key, slot) in [("after", &mut query.after), ("before", &mut query.before)] {
+        if let Some(value) = params.get(key) {
+            match parse_search_time(value) {
+                Some(t) => *slot = Some(t),
+                None => {
+                    return bad_request(format!("Invalid '{key}': expected RFC 3339 or YYYY-MM-DD"))
+                }
+            }
+        }
+    }
+    query.limit = params
+        .get("limit")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    query.offset = params
+        .get("offset")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    match state.kernel.memory.session_search_async(query).await {
+        Ok(results) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "query": text,
+                "count": results.len(),
+                "results": results,
+            })),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        ),
+    }
+}
+
+/// Parse an RFC 3339 timestamp or a `YYYY-MM-DD` date (midnight UTC).
+fn parse_search_time(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    if let Ok(t) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(t.with_timezone(&chrono::Utc));
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+}
+
 /// DELETE /api/sessions/:id — Delete a session.
 pub async fn delete_session(
     State(state): State<Arc<AppState>>,
@@ -325,6 +325,16 @@ pub async fn build_router(
                 .put(routes::set_agent_kv_key)
                 .delete(routes::delete_agent_kv_key),
         )
+        .route(
+            "/api/memory/export",
+            axum::routing::get(routes::export_memory),
+        )
+        .route(
+            "/api/memory/import",
+            // Snapshots with embeddings and sessions easily exceed axum's 2 MB default.
+            axum::routing::post(routes::import_memory)
+                .layer(axum::extract::DefaultBodyLimit::max(256 * 1024 * 1024)),
+        )
         // Trigger endpoints
         .route(
             "/api/triggers",
@@ -556,6 +566,10 @@ pub async fn build_router(
         )
         // Session endpoints
         .route("/api/sessions", axum::routing::get(routes::list_sessions))
+        .route(
+            "/api/sessions/search",
+            axum::routing::get(routes::search_sessions),
+        )
         .route(
             "/api/sessions/{id}",
             axum::routing::delete(routes::delete_session),
@@ -140,6 +140,18 @@ async fn start_test_server_with_provider(
             "/api/cron/jobs",
             axum::routing::get(routes::list_cron_jobs).post(routes::create_cron_job),
         )
+        .route(
+            "/api/memory/export",
+            axum::routing::get(routes::export_memory),
+        )
+        .route(
+            "/api/memory/import",
+            axum::routing::post(routes::import_memory),
+        )
+        .route(
+            "/api/sessions/search",
+            axum::routing::get(routes::search_sessions),
+        )
         .layer(axum::middleware::from_fn(middleware::request_logging))
         .layer(TraceLayer::new_for_http())
         .layer(CorsLayer::permissive())
@@ -626,6 +638,118 @@ async fn test_trigger_crud() {
     assert_eq!(triggers.len(), 0);
 }
 
+#[tokio::test]
+async fn test_memory_export_import_roundtrip() {
+    use openfang_types::memory::Memory;
+
+    let server = start_test_server().await;
+    let client = reqwest::Client::new();
+    let agent_id = openfang_types::agent::AgentId::new();
+    server
+        .state
+        .kernel
+        .memory
+        .set(agent_id, "project", serde_json::json!("apollo"))
+        .await
+        .unwrap();
+
+    for format in ["json", "msgpack"] {
+        let resp = client
+            .get(format!(
+                "{}/api/memory/export?format={format}",
+                server.base_url
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+        let data = resp.bytes().await.unwrap();
+        assert!(!data.is_empty());
+
+        let resp = client
+            .post(format!(
+                "{}/api/memory/import?format={format}&mode=replace",
+                server.base_url
+            ))
+            .body(data)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+        let report: serde_json::Value = resp.json().await.unwrap();
+        assert!(report["kv_imported"].as_u64().unwrap() >= 1);
+        assert!(report["errors"].as_array().unwrap().is_empty());
+    }
+
+    let value = server
+        .state
+        .kernel
+        .memory
+        .get(agent_id, "project")
+        .await
+        .unwrap();
+    assert_eq!(value, Some(serde_json::json!("apollo")));
+
+    // Unknown format and garbage payloads are rejected.
+    let resp = client
+        .get(format!("{}/api/memory/export?format=xml", server.base_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client
+        .post(format!("{}/api/memory/import", server.base_url))
+        .body("not json")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_session_search() {
+    use openfang_types::message::Message;
+
+    let server = start_test_server().await;
+    let client = reqwest::Client::new();
+    let memory = &server.state.kernel.memory;
+    let agent_id = openfang_types::agent::AgentId::new();
+    let mut session = memory.create_session(agent_id).unwrap();
+    session.messages = vec![
+        Message::user("The invoice for March was charged twice"),
+        Message::assistant("I have flagged the duplicate invoice"),
+    ];
+    memory.save_session(&session).unwrap();
+
+    let resp = client
+        .get(format!(
+            "{}/api/sessions/search?q=invoice&agent={agent_id}&role=user&after=2020-01-01",
+            server.base_url
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["count"], 1);
+    let hit = &body["results"][0];
+    assert_eq!(hit["session_id"], session.id.0.to_string());
+    assert_eq!(hit["role"], "user");
+    assert!(hit["snippet"]
+        .as_str()
+        .unwrap()
+        .contains("<mark>invoice</mark>"));
+
+    for bad in ["", "?q=invoice&role=tool", "?q=invoice&before=yesterday"] {
+        let resp = client
+            .get(format!("{}/api/sessions/search{bad}", server.base_url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 400, "expected 400 for '{bad}'");
+    }
+}
+
 #[tokio::test]
 async fn test_invalid_agent_id_returns_400() {
     let server = start_test_server().await;
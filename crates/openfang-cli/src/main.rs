@@ -769,6 +769,34 @@ enum MemoryCommands {
         /// Key name.
         key: String,
     },
+    /// Export memory (KV, memories, knowledge graph, sessions) to a file.
+    Export {
+        /// Output file path.
+        output: PathBuf,
+        /// Only export data owned by this agent (name or ID).
+        #[arg(long)]
+        agent: Option<String>,
+        /// Snapshot format: json or msgpack.
+        #[arg(long, default_value = "json")]
+        format: String,
+        /// Leave sessions out of the snapshot.
+        #[arg(long)]
+        no_sessions: bool,
+    },
+    /// Import a memory snapshot produced by `memory export`.
+    Import {
+        /// Snapshot file path.
+        input: PathBuf,
+        /// Snapshot format: json or msgpack (default: inferred from extension).
+        #[arg(long)]
+        format: Option<String>,
+        /// Replace existing data instead of merging into it.
+        #[arg(long)]
+        replace: bool,
+        /// Output the import report as JSON.
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -1110,6 +1138,18 @@ fn main() {
             MemoryCommands::Get { agent, key, json } => cmd_memory_get(&agent, &key, json),
             MemoryCommands::Set { agent, key, value } => cmd_memory_set(&agent, &key, &value),
             MemoryCommands::Delete { agent, key } => cmd_memory_delete(&agent, &key),
+            MemoryCommands::Export {
+                output,
+                agent,
+                format,
+                no_sessions,
+            } => cmd_memory_export(&output, agent.as_deref(), &format, no_sessions),
+            MemoryCommands::Import {
+                input,
+                format,
+                replace,
+                json,
+            } => cmd_memory_import(&input, format.as_deref(), replace, json),
         },
         Some(Commands::Devices(sub)) => match sub {
             DevicesCommands::List { json } => cmd_devices_list(json),
@@ -6504,6 +6544,127 @@ fn cmd_memory_delete(agent: &str, key: &str) {
     }
 }
 
+fn cmd_memory_export(
+    output: &std::path::Path,
+    agent: Option<&str>,
+    format: &str,
+    no_sessions: bool,
+) {
+    if openfang_types::memory::ExportFormat::parse(format).is_none() {
+        ui::error(&format!(
+            "Unknown format '{format}' (expected json or msgpack)"
+        ));
+        std::process::exit(1);
+    }
+    let base = require_daemon("memory export");
+    let client = daemon_client();
+    let mut query: Vec<(&str, &str)> = vec![("format", format)];
+    if let Some(a) = agent {
+        query.push(("agent", a));
+    }
+    if no_sessions {
+        query.push(("sessions", "false"));
+    }
+    let resp = match client
+        .get(format!("{base}/api/memory/export"))
+        .query(&query)
+        .send()
+    {
+        Ok(r) => r,
+        Err(e) => {
+            ui::error(&format!("Request failed: {e}"));
+            std::process::exit(1);
+        }
+    };
+    if !resp.status().is_success() {
+        let body = resp.json::<serde_json::Value>().unwrap_or_default();
+        ui::error(&format!(
+            "Export failed: {}",
+            body["error"].as_str().unwrap_or("unknown error")
+        ));
+        std::process::exit(1);
+    }
+    let data = match resp.bytes() {
+        Ok(b) => b,
+        Err(e) => {
+            ui::error(&format!("Failed to read export: {e}"));
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = std::fs::write(output, &data) {
+        ui::error(&format!("Failed to write {}: {e}", output.display()));
+        std::process::exit(1);
+    }
+    ui::success(&format!(
+        "Exported memory to {} ({} bytes).",
+        output.display(),
+        data.len()
+    ));
+}
+
+fn cmd_memory_import(input: &std::path::Path, format: Option<&str>, replace: bool, json: bool) {
+    use openfang_types::memory::ExportFormat;
+
+    let format = match format {
+        Some(f) => ExportFormat::parse(f),
+        None => match input.extension().and_then(|e| e.to_str()) {
+            Some(ext) => ExportFormat::parse(ext),
+            None => Some(ExportFormat::Json),
+        },
+    };
+    let Some(format) = format else {
+        ui::error("Cannot determine snapshot format; pass --format json|msgpack");
+        std::process::exit(1);
+    };
+    let data = match std::fs::read(input) {
+        Ok(d) => d,
+        Err(e) => {
+            ui::error(&format!("Failed to read {}: {e}", input.display()));
+            std::process::exit(1);
+        }
+    };
+    let base = require_daemon("memory import");
+    let client = daemon_client();
+    let body = daemon_json(
+        client
+            .post(format!("{base}/api/memory/import"))
+            .query(&[
+                ("format", format.extension()),
+                ("mode", if replace { "replace" } else { "merge" }),
+            ])
+            .header("content-type", format.content_type())
+            .body(data)
+            .send(),
+    );
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&body).unwrap_or_default()
+        );
+        return;
+    }
+    if let Some(err) = body["error"].as_str() {
+        ui::error(&format!("Import failed: {err}"));
+        std::process::exit(1);
+    }
+    ui::success(&format!(
+        "Imported {} KV pairs, {} memories, {} entities, {} relations, {} sessions.",
+        body["kv_imported"].as_u64().unwrap_or(0),
+        body["memories_imported"].as_u64().unwrap_or(0),
+        body["entities_imported"].as_u64().unwrap_or(0),
+        body["relations_imported"].as_u64().unwrap_or(0),
+        body["sessions_imported"].as_u64().unwrap_or(0),
+    ));
+    if let Some(errors) = body["errors"].as_array() {
+        if !errors.is_empty() {
+            ui::hint(&format!("{} records failed:", errors.len()));
+            for e in errors.iter().take(20) {
+                println!("  {}", e.as_str().unwrap_or("?"));
+            }
+        }
+    }
+}
+
 fn cmd_devices_list(json: bool) {
     let base = require_daemon("devices list");
     let client = daemon_client();
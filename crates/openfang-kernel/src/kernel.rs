@@ -4324,7 +4324,8 @@ impl OpenFangKernel {
             });
         }
 
-        // Periodic memory consolidation (decays stale memory confidence)
+        // Periodic memory consolidation (decays stale memory confidence,
+        // merges near-duplicate memories)
         {
             let interval_hours = self.config.memory.consolidation_interval_hours;
             if interval_hours > 0 {
@@ -4339,24 +4340,47 @@ impl OpenFangKernel {
                         if kernel.supervisor.is_shutting_down() {
                             break;
                         }
-                        match kernel.memory.consolidate().await {
-                            Ok(report) => {
-                                if report.memories_decayed > 0 || report.memories_merged > 0 {
-                                    info!(
-                                        merged = report.memories_merged,
-                                        decayed = report.memories_decayed,
-                                        duration_ms = report.duration_ms,
-                                        "Memory consolidation completed"
-                                    );
-                                }
-                            }
+                        kernel.run_memory_consolidation().await;
+                    }
+                });
+                info!("Memory consolidation scheduled every {interval_hours} hour(s)");
+            }
+        }
+
+        // Threshold-triggered consolidation: when any agent holds more than
+        // `consolidation_threshold` memories, consolidate without waiting for
+        // the periodic run. Re-triggers only after another 10% growth so a
+        // store full of distinct memories doesn't re-cluster every check.
+        {
+            let threshold = self.config.memory.consolidation_threshold;
+            if threshold > 0 {
+                let kernel = Arc::clone(self);
+                tokio::spawn(async move {
+                    let mut interval =
+                        tokio::time::interval(std::time::Duration::from_secs(10 * 60));
+                    interval.tick().await; // Skip first immediate tick
+                    let mut rearm_at = threshold;
+                    loop {
+                        interval.tick().await;
+                        if kernel.supervisor.is_shutting_down() {
+                            break;
+                        }
+                        let count = match kernel.memory.max_agent_memory_count() {
+                            Ok(c) => c,
                             Err(e) => {
-                                warn!("Memory consolidation failed: {e}");
+                                warn!("Memory count check failed: {e}");
+                                continue;
                             }
+                        };
+                        if count <= rearm_at {
+                            continue;
                         }
+                        info!(count, threshold, "Memory threshold exceeded, consolidating");
+                        kernel.run_memory_consolidation().await;
+                        let after = kernel.memory.max_agent_memory_count().unwrap_or(count);
+                        rearm_at = threshold.max(after + threshold / 10);
                     }
                 });
-                info!("Memory consolidation scheduled every {interval_hours} hour(s)");
             }
         }
 
@@ -5321,6 +5345,37 @@ impl OpenFangKernel {
         Ok(primary)
     }
 
+    /// Run one memory consolidation pass and log the outcome.
+    ///
+    /// When `memory.consolidation_llm_summary` is set, the default LLM writes
+    /// the merged text for each cluster of near-duplicate memories.
+    async fn run_memory_consolidation(&self) {
+        let summarizer: Option<Arc<dyn openfang_memory::consolidation::MemorySummarizer>> =
+            if self.config.memory.consolidation_llm_summary {
+                Some(Arc::new(LlmMemorySummarizer {
+                    driver: Arc::clone(&self.default_driver),
+                    model: self.config.default_model.model.clone(),
+                }))
+            } else {
+                None
+            };
+        match self.memory.consolidate_with_summarizer(summarizer).await {
+            Ok(report) => {
+                if report.memories_decayed > 0 || report.memories_merged > 0 {
+                    info!(
+                        merged = report.memories_merged,
+                        decayed = report.memories_decayed,
+                        duration_ms = report.duration_ms,
+                        "Memory consolidation completed"
+                    );
+                }
+            }
+            Err(e) => {
+                warn!("Memory consolidation failed: {e}");
+            }
+        }
+    }
+
     /// Connect to all configured MCP servers and cache their tool definitions.
     async fn connect_mcp_servers(self: &Arc<Self>) {
         use openfang_runtime::mcp::{McpConnection, McpServerConfig, McpTransport};
@@ -6598,6 +6653,49 @@ struct KernelCronBridge {
     kernel: Arc<OpenFangKernel>,
 }
 
+/// Writes merged memory text during consolidation using the default LLM.
+struct LlmMemorySummarizer {
+    driver: Arc<dyn LlmDriver>,
+    model: String,
+}
+
+#[async_trait]
+impl openfang_memory::consolidation::MemorySummarizer for LlmMemorySummarizer {
+    async fn summarize(&self, contents: &[String]) -> Result<String, String> {
+        let listing = contents
+            .iter()
+            .map(|c| format!("- {c}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let request = CompletionRequest {
+            model: self.model.clone(),
+            messages: vec![openfang_types::message::Message::user(format!(
+                "These memories describe the same thing:\n{listing}\n\n\
+                 Write them as a single memory."
+            ))],
+            tools: vec![],
+            max_tokens: 512,
+            temperature: 0.2,
+            system: Some(
+                "You merge near-duplicate agent memories. Keep every distinct fact, \
+                 drop repetition, and output only the merged memory text."
+                    .to_string(),
+            ),
+            thinking: None,
+        };
+        let response = self
+            .driver
+            .complete(request)
+            .await
+            .map_err(|e| e.to_string())?;
+        let text = response.text();
+        if text.trim().is_empty() {
+            return Err("LLM returned an empty merge summary".to_string());
+        }
+        Ok(text)
+    }
+}
+
 #[async_trait]
 impl openfang_channels::bridge::ChannelBridgeHandle for KernelCronBridge {
     async fn send_message(&self, _agent_id: AgentId, _message: &str) -> Result<String, String> {
@@ -6759,6 +6857,48 @@ impl KernelHandle for OpenFangKernel {
             .map_err(|e| format!("Memory recall failed: {e}"))
     }
 
+    async fn memory_search(
+        &self,
+        agent_id: &str,
+        query: &str,
+        limit: usize,
+        mut filter: openfang_types::memory::MemoryFilter,
+    ) -> Result<Vec<openfang_types::memory::MemoryFragment>, String> {
+        let id: AgentId = agent_id
+            .parse()
+            .map_err(|_| format!("Invalid agent ID: {agent_id}"))?;
+        filter.agent_id = Some(id);
+        let embedding = match (&self.embedding_driver, query.is_empty()) {
+            (Some(emb), false) => match emb.embed_one(query).await {
+                Ok(v) => Some(v),
+                Err(e) => {
+                    warn!("Embedding for memory search failed, using text search: {e}");
+                    None
+                }
+            },
+            _ => None,
+        };
+        self.memory
+            .recall_with_embedding_async(query, limit, Some(filter), embedding.as_deref())
+            .await
+            .map_err(|e| format!("Memory search failed: {e}"))
+    }
+
+    async fn session_search(
+        &self,
+        agent_id: &str,
+        mut query: openfang_memory::session::SessionSearchQuery,
+    ) -> Result<Vec<openfang_memory::session::SessionSearchHit>, String> {
+        let id: AgentId = agent_id
+            .parse()
+            .map_err(|_| format!("Invalid agent ID: {agent_id}"))?;
+        query.agent_id = Some(id);
+        self.memory
+            .session_search_async(query)
+            .await
+            .map_err(|e| format!("Session search failed: {e}"))
+    }
+
     fn find_agents(&self, query: &str) -> Vec<kernel_handle::AgentInfo> {
         let q = query.to_lowercase();
         self.registry
@@ -6871,6 +7011,44 @@ impl KernelHandle for OpenFangKernel {
             .map_err(|e| format!("Knowledge query failed: {e}"))
     }
 
+    async fn knowledge_traverse(
+        &self,
+        pattern: openfang_types::memory::GraphPattern,
+    ) -> Result<Vec<openfang_types::memory::GraphPath>, String> {
+        self.memory
+            .traverse_graph(pattern)
+            .await
+            .map_err(|e| format!("Knowledge traversal failed: {e}"))
+    }
+
+    async fn knowledge_shortest_path(
+        &self,
+        from: &str,
+        to: &str,
+        max_depth: u32,
+        relation: Option<openfang_types::memory::RelationType>,
+        min_confidence: f32,
+    ) -> Result<Option<openfang_types::memory::GraphPath>, String> {
+        self.memory
+            .shortest_path(from, to, max_depth, relation, min_confidence)
+            .await
+            .map_err(|e| format!("Knowledge shortest path failed: {e}"))
+    }
+
+    async fn knowledge_neighborhood(
+        &self,
+        center: &str,
+        depth: u32,
+        relation: Option<openfang_types::memory::RelationType>,
+        min_confidence: f32,
+        limit: usize,
+    ) -> Result<Option<openfang_types::memory::GraphNeighborhood>, String> {
+        self.memory
+            .graph_neighborhood(center, depth, relation, min_confidence, limit)
+            .await
+            .map_err(|e| format!("Knowledge neighborhood failed: {e}"))
+    }
+
     /// Spawn with capability inheritance enforcement.
     /// Parses the child manifest, extracts its capabilities, and verifies
     /// every child capability is covered by the parent's grants.
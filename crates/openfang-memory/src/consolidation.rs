@@ -2,12 +2,83 @@
 //!
 //! Reduces confidence of old, unaccessed memories and merges
 //! duplicate/similar memories.
+//!
+//! Merging clusters memories per agent and scope. Two memories are
+//! near-duplicates when their stored embeddings have cosine similarity at or
+//! above the embedding threshold, or — when either side has no embedding —
+//! when their word sets overlap (Jaccard) at or above the text threshold.
+//! Each cluster collapses into a single new fragment; the originals are
+//! soft-deleted with `merged_into` pointing at it.
 
+use async_trait::async_trait;
 use chrono::Utc;
 use openfang_types::error::{OpenFangError, OpenFangResult};
-use openfang_types::memory::ConsolidationReport;
+use openfang_types::memory::{ConsolidationReport, MemoryId};
 use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+/// Default cosine similarity for treating two embedded memories as duplicates.
+pub const DEFAULT_EMBEDDING_SIMILARITY: f32 = 0.92;
+
+/// Default word-set Jaccard similarity for memories without embeddings.
+pub const DEFAULT_TEXT_SIMILARITY: f32 = 0.85;
+
+/// Upper bound on memories scanned per (agent, scope) group in one pass.
+/// Clustering is quadratic, so very large groups are processed most-recent first.
+const MAX_GROUP_SCAN: usize = 5000;
+
+/// Writes the merged text for a cluster of near-duplicate memories.
+///
+/// Implemented by the kernel on top of an LLM driver; when absent, the
+/// highest-confidence member's content is kept verbatim.
+#[async_trait]
+pub trait MemorySummarizer: Send + Sync {
+    /// Produce one memory that preserves every fact in `contents`.
+    async fn summarize(&self, contents: &[String]) -> Result<String, String>;
+}
+
+/// One memory considered for merging.
+#[derive(Debug, Clone)]
+pub struct ClusterMember {
+    /// Memory row ID.
+    pub id: String,
+    /// Text content.
+    pub content: String,
+    /// Serialized source (JSON string, as stored).
+    pub source: String,
+    /// Confidence score.
+    pub confidence: f64,
+    /// Parsed metadata.
+    pub metadata: HashMap<String, serde_json::Value>,
+    /// Creation time (RFC 3339).
+    pub created_at: String,
+    /// Last access time (RFC 3339).
+    pub accessed_at: String,
+    /// Access count.
+    pub access_count: i64,
+    /// Stored embedding, if any.
+    pub embedding: Option<Vec<f32>>,
+}
+
+/// A group of near-duplicate memories that will be merged into one.
+#[derive(Debug, Clone)]
+pub struct MergeCluster {
+    /// Owning agent ID.
+    pub agent_id: String,
+    /// Memory scope.
+    pub scope: String,
+    /// Members, representative first.
+    pub members: Vec<ClusterMember>,
+}
+
+impl MergeCluster {
+    /// Contents of all members, representative first.
+    pub fn contents(&self) -> Vec<String> {
+        self.members.iter().map(|m| m.content.clone()).collect()
+    }
+}
 
 /// Memory consolidation engine.
 #[derive(Clone)]
@@ -15,23 +86,54 @@ pub struct ConsolidationEngine {
     conn: Arc<Mutex<Connection>>,
     /// Decay rate: how much to reduce confidence per consolidation cycle.
     decay_rate: f32,
+    /// Cosine similarity threshold for embedded memories.
+    embedding_similarity: f32,
+    /// Jaccard similarity threshold for memories without embeddings.
+    text_similarity: f32,
 }
 
 impl ConsolidationEngine {
     /// Create a new consolidation engine.
     pub fn new(conn: Arc<Mutex<Connection>>, decay_rate: f32) -> Self {
-        Self { conn, decay_rate }
+        Self {
+            conn,
+            decay_rate,
+            embedding_similarity: DEFAULT_EMBEDDING_SIMILARITY,
+            text_similarity: DEFAULT_TEXT_SIMILARITY,
+        }
     }
 
-    /// Run a consolidation cycle: decay old memories.
+    /// Override the similarity thresholds used for merging.
+    pub fn with_similarity(mut self, embedding: f32, text: f32) -> Self {
+        self.embedding_similarity = embedding.clamp(0.0, 1.0);
+        self.text_similarity = text.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Run a consolidation cycle: decay old memories, then merge near-duplicates
+    /// keeping the representative's text.
     pub fn consolidate(&self) -> OpenFangResult<ConsolidationReport> {
         let start = std::time::Instant::now();
+        let decayed = self.decay()?;
+        let mut merged = 0u64;
+        for cluster in self.find_clusters()? {
+            self.apply_merge(&cluster, None)?;
+            merged += cluster.members.len() as u64;
+        }
+        Ok(ConsolidationReport {
+            memories_merged: merged,
+            memories_decayed: decayed,
+            duration_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Decay confidence of memories not accessed in the last 7 days.
+    pub fn decay(&self) -> OpenFangResult<u64> {
         let conn = self
             .conn
             .lock()
             .map_err(|e| OpenFangError::Internal(e.to_string()))?;
 
-        // Decay confidence of memories not accessed in the last 7 days
         let cutoff = (Utc::now() - chrono::Duration::days(7)).to_rfc3339();
         let decay_factor = 1.0 - self.decay_rate as f64;
 
@@ -42,17 +144,322 @@ impl ConsolidationEngine {
                 rusqlite::params![decay_factor, cutoff],
             )
             .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        Ok(decayed as u64)
+    }
 
-        let duration_ms = start.elapsed().as_millis() as u64;
+    /// Largest number of active memories held by any single agent.
+    pub fn max_agent_memory_count(&self) -> OpenFangResult<u64> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?;
+        let count: i64 = conn
+            .query_row(
+                "SELECT COALESCE(MAX(n), 0) FROM
+                 (SELECT COUNT(*) AS n FROM memories WHERE deleted = 0 GROUP BY agent_id)",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        Ok(count as u64)
+    }
 
-        Ok(ConsolidationReport {
-            memories_merged: 0, // Phase 1: no merging
-            memories_decayed: decayed as u64,
-            duration_ms,
-        })
+    /// Find clusters of near-duplicate memories (two or more members each).
+    pub fn find_clusters(&self) -> OpenFangResult<Vec<MergeCluster>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?;
+
+        let groups: Vec<(String, String)> = {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT agent_id, scope FROM memories WHERE deleted = 0
+                     GROUP BY agent_id, scope HAVING COUNT(*) > 1",
+                )
+                .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+            rows.collect::<Result<_, _>>()
+                .map_err(|e| OpenFangError::Memory(e.to_string()))?
+        };
+
+        let mut clusters = Vec::new();
+        for (agent_id, scope) in groups {
+            let members = load_group(&conn, &agent_id, &scope)?;
+            for group in self.cluster(members) {
+                clusters.push(MergeCluster {
+                    agent_id: agent_id.clone(),
+                    scope: scope.clone(),
+                    members: group,
+                });
+            }
+        }
+        debug!(
+            clusters = clusters.len(),
+            "Consolidation clustering complete"
+        );
+        Ok(clusters)
+    }
+
+    /// Leader clustering: each unassigned memory seeds a cluster and absorbs
+    /// every later unassigned memory similar to it.
+    fn cluster(&self, members: Vec<ClusterMember>) -> Vec<Vec<ClusterMember>> {
+        let tokens: Vec<HashSet<String>> = members.iter().map(|m| word_set(&m.content)).collect();
+        let mut assigned = vec![false; members.len()];
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+
+        for i in 0..members.len() {
+            if assigned[i] {
+                continue;
+            }
+            assigned[i] = true;
+            let mut group = vec![i];
+            for j in (i + 1)..members.len() {
+                if assigned[j] {
+                    continue;
+                }
+                if self.is_duplicate(&members[i], &tokens[i], &members[j], &tokens[j]) {
+                    assigned[j] = true;
+                    group.push(j);
+                }
+            }
+            if group.len() > 1 {
+                groups.push(group);
+            }
+        }
+
+        let mut slots: Vec<Option<ClusterMember>> = members.into_iter().map(Some).collect();
+        groups
+            .into_iter()
+            .map(|idxs| {
+                let mut group: Vec<ClusterMember> =
+                    idxs.into_iter().filter_map(|i| slots[i].take()).collect();
+                // Representative first: most trusted, then most used, then most detailed.
+                group.sort_by(|a, b| {
+                    b.confidence
+                        .partial_cmp(&a.confidence)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                        .then(b.access_count.cmp(&a.access_count))
+                        .then(b.content.len().cmp(&a.content.len()))
+                });
+                group
+            })
+            .collect()
+    }
+
+    fn is_duplicate(
+        &self,
+        a: &ClusterMember,
+        a_tokens: &HashSet<String>,
+        b: &ClusterMember,
+        b_tokens: &HashSet<String>,
+    ) -> bool {
+        match (&a.embedding, &b.embedding) {
+            (Some(ea), Some(eb)) if ea.len() == eb.len() && !ea.is_empty() => {
+                cosine_similarity(ea, eb) >= self.embedding_similarity
+            }
+            _ => jaccard(a_tokens, b_tokens) >= self.text_similarity,
+        }
+    }
+
+    /// Merge a cluster into a single new memory and soft-delete the originals.
+    ///
+    /// `summary` replaces the representative's text when provided (e.g. from
+    /// an LLM). Returns the ID of the merged memory.
+    pub fn apply_merge(
+        &self,
+        cluster: &MergeCluster,
+        summary: Option<&str>,
+    ) -> OpenFangResult<MemoryId> {
+        let Some(rep) = cluster.members.first() else {
+            return Err(OpenFangError::Memory("Empty merge cluster".to_string()));
+        };
+        let merged_id = MemoryId::new();
+        let now = Utc::now().to_rfc3339();
+
+        // Combined metadata: members in reverse so the representative's keys win.
+        let mut metadata: HashMap<String, serde_json::Value> = HashMap::new();
+        for m in cluster.members.iter().rev() {
+            metadata.extend(m.metadata.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        let source_ids: Vec<&str> = cluster.members.iter().map(|m| m.id.as_str()).collect();
+        metadata.insert("merged_from".to_string(), serde_json::json!(source_ids));
+        metadata.insert("merged_at".to_string(), serde_json::json!(now));
+        let meta_str = serde_json::to_string(&metadata)
+            .map_err(|e| OpenFangError::Serialization(e.to_string()))?;
+
+        let content = summary
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(&rep.content);
+        let confidence = cluster
+            .members
+            .iter()
+            .map(|m| m.confidence)
+            .fold(0.0, f64::max);
+        let access_count: i64 = cluster.members.iter().map(|m| m.access_count).sum();
+        let created_at = cluster
+            .members
+            .iter()
+            .map(|m| m.created_at.as_str())
+            .min()
+            .unwrap_or(&now);
+        let accessed_at = cluster
+            .members
+            .iter()
+            .map(|m| m.accessed_at.as_str())
+            .max()
+            .unwrap_or(&now);
+        let embedding = mean_embedding(&cluster.members).map(|e| embedding_to_bytes(&e));
+
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        tx.execute(
+            "INSERT INTO memories (id, agent_id, content, source, scope, confidence, metadata, created_at, accessed_at, access_count, deleted, embedding)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 0, ?11)",
+            rusqlite::params![
+                merged_id.0.to_string(),
+                cluster.agent_id,
+                content,
+                rep.source,
+                cluster.scope,
+                confidence,
+                meta_str,
+                created_at,
+                accessed_at,
+                access_count,
+                embedding,
+            ],
+        )
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        for m in &cluster.members {
+            tx.execute(
+                "UPDATE memories SET deleted = 1, merged_into = ?1 WHERE id = ?2",
+                rusqlite::params![merged_id.0.to_string(), m.id],
+            )
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        }
+        tx.commit()
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        Ok(merged_id)
+    }
+}
+
+/// Load the active memories of one (agent, scope) group, oldest first.
+fn load_group(
+    conn: &Connection,
+    agent_id: &str,
+    scope: &str,
+) -> OpenFangResult<Vec<ClusterMember>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, content, source, confidence, metadata, created_at, accessed_at, access_count, embedding
+             FROM memories WHERE deleted = 0 AND agent_id = ?1 AND scope = ?2
+             ORDER BY created_at DESC LIMIT ?3",
+        )
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+    let rows = stmt
+        .query_map(
+            rusqlite::params![agent_id, scope, MAX_GROUP_SCAN as i64],
+            |row| {
+                let meta_str: String = row.get(4)?;
+                let embedding: Option<Vec<u8>> = row.get(8)?;
+                Ok(ClusterMember {
+                    id: row.get(0)?,
+                    content: row.get(1)?,
+                    source: row.get(2)?,
+                    confidence: row.get(3)?,
+                    metadata: serde_json::from_str(&meta_str).unwrap_or_default(),
+                    created_at: row.get(5)?,
+                    accessed_at: row.get(6)?,
+                    access_count: row.get(7)?,
+                    embedding: embedding.as_deref().map(embedding_from_bytes),
+                })
+            },
+        )
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+    let mut members: Vec<ClusterMember> = rows
+        .collect::<Result<_, _>>()
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+    members.reverse();
+    Ok(members)
+}
+
+/// Lowercased alphanumeric word set used for text similarity.
+fn word_set(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Jaccard similarity of two word sets.
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let intersection = a.intersection(b).count();
+    let union = a.len() + b.len() - intersection;
+    intersection as f32 / union as f32
+}
+
+/// Compute cosine similarity between two vectors.
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let mut dot = 0.0f32;
+    let mut norm_a = 0.0f32;
+    let mut norm_b = 0.0f32;
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    let denom = norm_a.sqrt() * norm_b.sqrt();
+    if denom < f32::EPSILON {
+        0.0
+    } else {
+        dot / denom
     }
 }
 
+/// Element-wise mean of the members' embeddings that match the representative's dimension.
+fn mean_embedding(members: &[ClusterMember]) -> Option<Vec<f32>> {
+    let dim = members.first()?.embedding.as_ref()?.len();
+    let mut sum = vec![0.0f32; dim];
+    let mut n = 0usize;
+    for e in members.iter().filter_map(|m| m.embedding.as_ref()) {
+        if e.len() == dim {
+            for (s, v) in sum.iter_mut().zip(e) {
+                *s += v;
+            }
+            n += 1;
+        }
+    }
+    if n == 0 {
+        return None;
+    }
+    Some(sum.into_iter().map(|s| s / n as f32).collect())
+}
+
+/// Serialize embedding to bytes for SQLite BLOB storage.
+fn embedding_to_bytes(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// Deserialize embedding from bytes.
+fn embedding_from_bytes(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -64,11 +471,49 @@ mod tests {
         ConsolidationEngine::new(Arc::new(Mutex::new(conn)), 0.1)
     }
 
+    fn insert(
+        engine: &ConsolidationEngine,
+        id: &str,
+        agent: &str,
+        content: &str,
+        embedding: Option<&[f32]>,
+        access_count: i64,
+    ) {
+        let conn = engine.conn.lock().unwrap();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO memories (id, agent_id, content, source, scope, confidence, metadata, created_at, accessed_at, access_count, deleted, embedding)
+             VALUES (?1, ?2, ?3, '\"conversation\"', 'episodic', 0.9, ?4, ?5, ?5, ?6, 0, ?7)",
+            rusqlite::params![
+                id,
+                agent,
+                content,
+                format!("{{\"from\":\"{id}\"}}"),
+                now,
+                access_count,
+                embedding.map(embedding_to_bytes),
+            ],
+        )
+        .unwrap();
+    }
+
+    fn active(engine: &ConsolidationEngine) -> Vec<(String, String, i64)> {
+        let conn = engine.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT id, content, access_count FROM memories WHERE deleted = 0 ORDER BY id")
+            .unwrap();
+        stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+    }
+
     #[test]
     fn test_consolidation_empty() {
         let engine = setup();
         let report = engine.consolidate().unwrap();
         assert_eq!(report.memories_decayed, 0);
+        assert_eq!(report.memories_merged, 0);
     }
 
     #[test]
@@ -98,4 +543,118 @@ mod tests {
             .unwrap();
         assert!(confidence < 0.9);
     }
+
+    #[test]
+    fn test_merges_embedded_near_duplicates() {
+        let engine = setup();
+        insert(
+            &engine,
+            "a",
+            "agent-1",
+            "User likes dark mode",
+            Some(&[1.0, 0.0, 0.01]),
+            2,
+        );
+        insert(
+            &engine,
+            "b",
+            "agent-1",
+            "The user prefers dark mode",
+            Some(&[0.99, 0.0, 0.02]),
+            3,
+        );
+        insert(
+            &engine,
+            "c",
+            "agent-1",
+            "User lives in Berlin",
+            Some(&[0.0, 1.0, 0.0]),
+            1,
+        );
+        // Same text, different agent: never merged across agents.
+        insert(
+            &engine,
+            "d",
+            "agent-2",
+            "User likes dark mode",
+            Some(&[1.0, 0.0, 0.01]),
+            1,
+        );
+
+        let report = engine.consolidate().unwrap();
+        assert_eq!(report.memories_merged, 2);
+
+        let rows = active(&engine);
+        assert_eq!(rows.len(), 3);
+        let merged = rows
+            .iter()
+            .find(|(id, _, _)| !["a", "b", "c", "d"].contains(&id.as_str()))
+            .expect("merged memory exists");
+        assert_eq!(merged.2, 5, "access counts are summed");
+
+        // Originals carry a provenance link to the merged memory.
+        let conn = engine.conn.lock().unwrap();
+        let (merged_into, meta): (String, String) = conn
+            .query_row(
+                "SELECT m.merged_into, n.metadata FROM memories m JOIN memories n ON n.id = m.merged_into WHERE m.id = 'a'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(merged_into, merged.0);
+        let meta: serde_json::Value = serde_json::from_str(&meta).unwrap();
+        assert_eq!(meta["merged_from"].as_array().unwrap().len(), 2);
+        assert!(meta.get("from").is_some(), "member metadata is combined");
+    }
+
+    #[test]
+    fn test_text_fallback_without_embeddings() {
+        let engine = setup().with_similarity(0.92, 0.8);
+        insert(
+            &engine,
+            "a",
+            "agent-1",
+            "Deploys happen every Friday at noon",
+            None,
+            0,
+        );
+        insert(
+            &engine,
+            "b",
+            "agent-1",
+            "deploys happen every friday at noon.",
+            None,
+            0,
+        );
+        insert(
+            &engine,
+            "c",
+            "agent-1",
+            "The staging database is Postgres 16",
+            None,
+            0,
+        );
+
+        let clusters = engine.find_clusters().unwrap();
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].members.len(), 2);
+
+        let id = engine
+            .apply_merge(&clusters[0], Some("Deploys run every Friday at 12:00."))
+            .unwrap();
+        let rows = active(&engine);
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().any(|(rid, content, _)| *rid == id.0.to_string()
+            && content == "Deploys run every Friday at 12:00."));
+    }
+
+    #[test]
+    fn test_max_agent_memory_count() {
+        let engine = setup();
+        assert_eq!(engine.max_agent_memory_count().unwrap(), 0);
+        insert(&engine, "a", "agent-1", "one", None, 0);
+        insert(&engine, "b", "agent-1", "two", None, 0);
+        insert(&engine, "c", "agent-2", "three", None, 0);
+        assert_eq!(engine.max_agent_memory_count().unwrap(), 2);
+    }
 }
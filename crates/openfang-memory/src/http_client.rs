@@ -5,7 +5,9 @@
 //! Designed to be called from synchronous SemanticStore methods within
 //! `spawn_blocking` contexts.
 
+use openfang_types::memory::MetadataPredicate;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tracing::{debug, warn};
 
 /// Error type for memory API operations.
@@ -60,6 +62,23 @@ struct SearchRequest<'a> {
     limit: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     category: Option<&'a str>,
+    #[serde(flatten)]
+    filters: &'a SearchFilters,
+}
+
+/// Optional recall filters forwarded to memory-api search.
+///
+/// Timestamps are epoch milliseconds, matching `createdAt` in results.
+#[derive(Serialize, Default, Debug, Clone)]
+pub struct SearchFilters {
+    #[serde(rename = "agentId", skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<String>,
+    #[serde(rename = "createdAfter", skip_serializing_if = "Option::is_none")]
+    pub created_after: Option<i64>,
+    #[serde(rename = "createdBefore", skip_serializing_if = "Option::is_none")]
+    pub created_before: Option<i64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub metadata: Vec<MetadataPredicate>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -78,6 +97,8 @@ pub struct SearchResult {
     pub score: f64,
     #[serde(rename = "createdAt", default)]
     pub created_at: Option<f64>,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -208,6 +229,7 @@ impl MemoryApiClient {
         query: &str,
         limit: usize,
         category: Option<&str>,
+        filters: &SearchFilters,
     ) -> Result<Vec<SearchResult>, MemoryApiError> {
         let url = format!("{}/memory/search", self.base_url);
 
@@ -215,6 +237,7 @@ impl MemoryApiClient {
             query,
             limit: Some(limit),
             category,
+            filters,
         };
 
         let mut req = self.client.post(&url).json(&body);
@@ -1,14 +1,16 @@
 //! Knowledge graph backed by SQLite.
 //!
-//! Stores entities and relations with support for graph pattern queries.
+//! Stores entities and relations with support for multi-hop pattern queries,
+//! shortest-path lookups and neighborhood expansion.
 
 use chrono::Utc;
 use openfang_types::error::{OpenFangError, OpenFangResult};
 use openfang_types::memory::{
-    Entity, EntityType, GraphMatch, GraphPattern, Relation, RelationType,
+    Entity, EntityType, GraphMatch, GraphNeighborhood, GraphPath, GraphPattern, Relation,
+    RelationType,
 };
 use rusqlite::Connection;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
@@ -80,143 +82,639 @@ impl KnowledgeStore {
     }
 
     /// Query the knowledge graph with a pattern.
+    ///
+    /// Returns every relation on the paths matched by [`Self::traverse`],
+    /// each relation once, in path order. With `max_depth <= 1` this is one
+    /// match per direct relation.
     pub fn query_graph(&self, pattern: GraphPattern) -> OpenFangResult<Vec<GraphMatch>> {
         let conn = self
             .conn
             .lock()
             .map_err(|e| OpenFangError::Internal(e.to_string()))?;
+        let mut graph = GraphReader::new(&conn, pattern.min_confidence);
+        let paths = graph.find_paths(&pattern)?;
 
-        let mut sql = String::from(
-            "SELECT
-                s.id, s.entity_type, s.name, s.properties, s.created_at, s.updated_at,
-                r.id, r.source_entity, r.relation_type, r.target_entity, r.properties, r.confidence, r.created_at,
-                t.id, t.entity_type, t.name, t.properties, t.created_at, t.updated_at
-             FROM relations r
-             JOIN entities s ON r.source_entity = s.id
-             JOIN entities t ON r.target_entity = t.id
-             WHERE 1=1",
-        );
-        let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
-        let mut idx = 1;
-
-        if let Some(ref source) = pattern.source {
-            sql.push_str(&format!(" AND (s.id = ?{} OR s.name = ?{})", idx, idx + 1));
-            params.push(Box::new(source.clone()));
-            params.push(Box::new(source.clone()));
-            idx += 2;
+        let mut seen = HashSet::new();
+        let mut matches = Vec::new();
+        for path in &paths {
+            for edge in &path.edges {
+                if !seen.insert(edge.id.clone()) {
+                    continue;
+                }
+                let (Some(source), Some(target)) =
+                    (graph.entity(&edge.source)?, graph.entity(&edge.target)?)
+                else {
+                    continue;
+                };
+                matches.push(GraphMatch {
+                    source,
+                    relation: edge.relation.clone(),
+                    target,
+                });
+            }
+        }
+        Ok(matches)
+    }
+
+    /// Find directed paths matching a pattern.
+    ///
+    /// Paths have 1 to `max_depth` hops (capped at [`MAX_GRAPH_DEPTH`]), never
+    /// revisit an entity, and only follow relations at or above
+    /// `min_confidence`. Results are ordered by hop count, then by path
+    /// confidence, and paginated with `offset`/`limit`.
+    pub fn traverse(&self, pattern: GraphPattern) -> OpenFangResult<Vec<GraphPath>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?;
+        let mut graph = GraphReader::new(&conn, pattern.min_confidence);
+        let paths = graph.find_paths(&pattern)?;
+        let mut out = Vec::with_capacity(paths.len());
+        for path in paths {
+            if let Some(p) = graph.build_path(&path.nodes, &path.edges)? {
+                out.push(p);
+            }
         }
-        if let Some(ref relation) = pattern.relation {
-            let rel_str = serde_json::to_string(relation)
-                .map_err(|e| OpenFangError::Serialization(e.to_string()))?;
-            sql.push_str(&format!(" AND r.relation_type = ?{idx}"));
-            params.push(Box::new(rel_str));
-            idx += 1;
+        Ok(out)
+    }
+
+    /// Find the shortest path between two entities (by ID or name).
+    ///
+    /// Relations are followed in either direction. Among equally short paths
+    /// the one found through higher-confidence relations wins. Returns `None`
+    /// if the entities are not connected within `max_depth` hops.
+    pub fn shortest_path(
+        &self,
+        from: &str,
+        to: &str,
+        max_depth: u32,
+        relation: Option<RelationType>,
+        min_confidence: f32,
+    ) -> OpenFangResult<Option<GraphPath>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?;
+        let mut graph = GraphReader::new(&conn, min_confidence);
+        let starts = graph.resolve(from)?;
+        let goals: HashSet<String> = graph.resolve(to)?.into_iter().collect();
+        if starts.is_empty() || goals.is_empty() {
+            return Ok(None);
         }
-        if let Some(ref target) = pattern.target {
-            sql.push_str(&format!(" AND (t.id = ?{} OR t.name = ?{})", idx, idx + 1));
-            params.push(Box::new(target.clone()));
-            params.push(Box::new(target.clone()));
-            idx += 2;
+        if let Some(start) = starts.iter().find(|s| goals.contains(*s)) {
+            return graph.build_path(std::slice::from_ref(start), &[]);
         }
-        let _ = idx;
 
-        sql.push_str(" LIMIT 100");
+        let max_depth = clamp_depth(max_depth);
+        let mut parent: HashMap<String, (String, Edge)> = HashMap::new();
+        let mut visited: HashSet<String> = starts.iter().cloned().collect();
+        let mut frontier = starts;
+        for _ in 0..max_depth {
+            let mut next = Vec::new();
+            for node in &frontier {
+                for (edge, neighbor) in graph.neighbors(node)? {
+                    if relation
+                        .as_ref()
+                        .is_some_and(|r| *r != edge.relation.relation)
+                    {
+                        continue;
+                    }
+                    if !visited.insert(neighbor.clone()) {
+                        continue;
+                    }
+                    parent.insert(neighbor.clone(), (node.clone(), edge));
+                    if goals.contains(&neighbor) {
+                        let mut nodes = vec![neighbor.clone()];
+                        let mut edges = Vec::new();
+                        let mut cur = neighbor;
+                        while let Some((prev, edge)) = parent.get(&cur) {
+                            edges.push(edge.clone());
+                            nodes.push(prev.clone());
+                            cur = prev.clone();
+                        }
+                        nodes.reverse();
+                        edges.reverse();
+                        return graph.build_path(&nodes, &edges);
+                    }
+                    next.push(neighbor);
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+        Ok(None)
+    }
 
-        let mut stmt = conn
-            .prepare(&sql)
+    /// Collect the entities within `depth` hops of an entity (by ID or name),
+    /// following relations in either direction, plus the relations between
+    /// them. Returns `None` if the entity does not exist.
+    pub fn neighborhood(
+        &self,
+        center: &str,
+        depth: u32,
+        relation: Option<RelationType>,
+        min_confidence: f32,
+        limit: usize,
+    ) -> OpenFangResult<Option<GraphNeighborhood>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?;
+        let mut graph = GraphReader::new(&conn, min_confidence);
+        let Some(center_id) = graph.resolve(center)?.into_iter().next() else {
+            return Ok(None);
+        };
+        let Some(center_entity) = graph.entity(&center_id)? else {
+            return Ok(None);
+        };
+        let limit = if limit == 0 {
+            DEFAULT_GRAPH_LIMIT
+        } else {
+            limit
+        };
+
+        let mut distance: HashMap<String, u32> = HashMap::new();
+        distance.insert(center_id.clone(), 0);
+        let mut order = Vec::new();
+        let mut edges: Vec<Edge> = Vec::new();
+        let mut edge_ids = HashSet::new();
+        let mut frontier = vec![center_id];
+        'outer: for hop in 1..=clamp_depth(depth) {
+            let mut next = Vec::new();
+            for node in &frontier {
+                for (edge, neighbor) in graph.neighbors(node)? {
+                    if relation
+                        .as_ref()
+                        .is_some_and(|r| *r != edge.relation.relation)
+                    {
+                        continue;
+                    }
+                    if !distance.contains_key(&neighbor) {
+                        if order.len() >= limit {
+                            break 'outer;
+                        }
+                        distance.insert(neighbor.clone(), hop);
+                        order.push(neighbor.clone());
+                        next.push(neighbor);
+                    }
+                    if edge_ids.insert(edge.id.clone()) {
+                        edges.push(edge);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+
+        let mut entities = Vec::with_capacity(order.len());
+        for id in &order {
+            if let Some(entity) = graph.entity(id)? {
+                entities.push((entity, distance[id]));
+            }
+        }
+        let relations = edges
+            .into_iter()
+            .filter(|e| distance.contains_key(&e.source) && distance.contains_key(&e.target))
+            .map(|e| e.relation)
+            .collect();
+        Ok(Some(GraphNeighborhood {
+            center: center_entity,
+            entities,
+            relations,
+        }))
+    }
+}
+
+/// Default number of results returned by graph queries.
+const DEFAULT_GRAPH_LIMIT: usize = 100;
+
+/// Maximum traversal depth accepted by graph queries.
+pub const MAX_GRAPH_DEPTH: u32 = 6;
+
+/// Upper bound on partial paths expanded by a single traversal, so a query
+/// over a dense graph can't run away.
+const MAX_EXPANDED_PATHS: usize = 10_000;
+
+fn clamp_depth(depth: u32) -> u32 {
+    depth.clamp(1, MAX_GRAPH_DEPTH)
+}
+
+/// A stored relation together with its row ID.
+#[derive(Clone)]
+struct Edge {
+    id: String,
+    source: String,
+    target: String,
+    relation: Relation,
+}
+
+/// A path under construction: `nodes` are entity IDs, `edges[i]` connects
+/// `nodes[i]` and `nodes[i + 1]`.
+#[derive(Clone)]
+struct PartialPath {
+    nodes: Vec<String>,
+    edges: Vec<Edge>,
+}
+
+impl PartialPath {
+    fn confidence(&self) -> f32 {
+        self.edges.iter().map(|e| e.relation.confidence).product()
+    }
+}
+
+/// Read-side helper for graph queries. Caches entities and adjacency lists
+/// for the duration of one query.
+struct GraphReader<'a> {
+    conn: &'a Connection,
+    min_confidence: f64,
+    entities: HashMap<String, Option<Entity>>,
+    outgoing: HashMap<String, Vec<Edge>>,
+    incoming: HashMap<String, Vec<Edge>>,
+}
+
+const RELATION_COLUMNS: &str =
+    "id, source_entity, relation_type, target_entity, properties, confidence, created_at";
+
+impl<'a> GraphReader<'a> {
+    fn new(conn: &'a Connection, min_confidence: f32) -> Self {
+        Self {
+            conn,
+            min_confidence: min_confidence as f64,
+            entities: HashMap::new(),
+            outgoing: HashMap::new(),
+            incoming: HashMap::new(),
+        }
+    }
+
+    /// IDs of the entities whose ID or name equals `key`.
+    fn resolve(&mut self, key: &str) -> OpenFangResult<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT id, entity_type, name, properties, created_at, updated_at
+                 FROM entities WHERE id = ?1 OR name = ?1",
+            )
             .map_err(|e| OpenFangError::Memory(e.to_string()))?;
-        let param_refs: Vec<&dyn rusqlite::types::ToSql> =
-            params.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt
+            .query_map(rusqlite::params![key], entity_from_row)
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        let mut ids = Vec::new();
+        for row in rows {
+            let entity = row.map_err(|e| OpenFangError::Memory(e.to_string()))?;
+            ids.push(entity.id.clone());
+            self.entities.insert(entity.id.clone(), Some(entity));
+        }
+        Ok(ids)
+    }
 
+    fn entity(&mut self, id: &str) -> OpenFangResult<Option<Entity>> {
+        if let Some(cached) = self.entities.get(id) {
+            return Ok(cached.clone());
+        }
+        let entity = self
+            .conn
+            .query_row(
+                "SELECT id, entity_type, name, properties, created_at, updated_at
+                 FROM entities WHERE id = ?1",
+                rusqlite::params![id],
+                entity_from_row,
+            )
+            .map(Some)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                other => Err(OpenFangError::Memory(other.to_string())),
+            })?;
+        self.entities.insert(id.to_string(), entity.clone());
+        Ok(entity)
+    }
+
+    fn query_edges(
+        &self,
+        filter: &str,
+        params: &[&dyn rusqlite::types::ToSql],
+    ) -> OpenFangResult<Vec<Edge>> {
+        let sql = format!(
+            "SELECT {RELATION_COLUMNS} FROM relations
+             WHERE confidence >= ?1 AND {filter}
+             ORDER BY confidence DESC, created_at ASC
+             LIMIT {MAX_EXPANDED_PATHS}"
+        );
+        let mut stmt = self
+            .conn
+            .prepare(&sql)
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        let mut all: Vec<&dyn rusqlite::types::ToSql> = vec![&self.min_confidence];
+        all.extend_from_slice(params);
         let rows = stmt
-            .query_map(param_refs.as_slice(), |row| {
-                Ok(RawGraphRow {
-                    s_id: row.get(0)?,
-                    s_type: row.get(1)?,
-                    s_name: row.get(2)?,
-                    s_props: row.get(3)?,
-                    s_created: row.get(4)?,
-                    s_updated: row.get(5)?,
-                    r_id: row.get(6)?,
-                    r_source: row.get(7)?,
-                    r_type: row.get(8)?,
-                    r_target: row.get(9)?,
-                    r_props: row.get(10)?,
-                    r_confidence: row.get(11)?,
-                    r_created: row.get(12)?,
-                    t_id: row.get(13)?,
-                    t_type: row.get(14)?,
-                    t_name: row.get(15)?,
-                    t_props: row.get(16)?,
-                    t_created: row.get(17)?,
-                    t_updated: row.get(18)?,
-                })
-            })
+            .query_map(all.as_slice(), edge_from_row)
             .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        rows.map(|r| r.map_err(|e| OpenFangError::Memory(e.to_string())))
+            .collect()
+    }
 
-        let mut matches = Vec::new();
-        for row_result in rows {
-            let r = row_result.map_err(|e| OpenFangError::Memory(e.to_string()))?;
-            matches.push(GraphMatch {
-                source: parse_entity(
-                    &r.s_id,
-                    &r.s_type,
-                    &r.s_name,
-                    &r.s_props,
-                    &r.s_created,
-                    &r.s_updated,
-                ),
-                relation: parse_relation(
-                    &r.r_source,
-                    &r.r_type,
-                    &r.r_target,
-                    &r.r_props,
-                    r.r_confidence,
-                    &r.r_created,
-                ),
-                target: parse_entity(
-                    &r.t_id,
-                    &r.t_type,
-                    &r.t_name,
-                    &r.t_props,
-                    &r.t_created,
-                    &r.t_updated,
-                ),
-            });
+    fn outgoing(&mut self, id: &str) -> OpenFangResult<Vec<Edge>> {
+        if let Some(edges) = self.outgoing.get(id) {
+            return Ok(edges.clone());
         }
-        Ok(matches)
+        let edges = self.query_edges("source_entity = ?2", &[&id])?;
+        self.outgoing.insert(id.to_string(), edges.clone());
+        Ok(edges)
+    }
+
+    fn incoming(&mut self, id: &str) -> OpenFangResult<Vec<Edge>> {
+        if let Some(edges) = self.incoming.get(id) {
+            return Ok(edges.clone());
+        }
+        let edges = self.query_edges("target_entity = ?2", &[&id])?;
+        self.incoming.insert(id.to_string(), edges.clone());
+        Ok(edges)
+    }
+
+    /// Relations touching `id` in either direction, paired with the entity on
+    /// the other end. Self-loops and dangling relations are skipped.
+    fn neighbors(&mut self, id: &str) -> OpenFangResult<Vec<(Edge, String)>> {
+        let mut out = Vec::new();
+        let mut edges = self.outgoing(id)?;
+        edges.extend(self.incoming(id)?);
+        edges.sort_by(|a, b| b.relation.confidence.total_cmp(&a.relation.confidence));
+        for edge in edges {
+            let other = if edge.source == id {
+                edge.target.clone()
+            } else {
+                edge.source.clone()
+            };
+            if other == id || self.entity(&other)?.is_none() {
+                continue;
+            }
+            out.push((edge, other));
+        }
+        Ok(out)
+    }
+
+    /// Whether hop `index` of a pattern may follow `edge` to reach `reached`.
+    fn hop_allowed(
+        &mut self,
+        pattern: &GraphPattern,
+        index: usize,
+        edge: &Edge,
+        reached: &str,
+    ) -> OpenFangResult<bool> {
+        let hop = pattern.hops.get(index);
+        let relation = hop
+            .and_then(|h| h.relation.as_ref())
+            .or(pattern.relation.as_ref());
+        if relation.is_some_and(|r| *r != edge.relation.relation) {
+            return Ok(false);
+        }
+        let Some(entity) = self.entity(reached)? else {
+            return Ok(false);
+        };
+        let entity_type = hop
+            .and_then(|h| h.entity_type.as_ref())
+            .or(pattern.entity_type.as_ref());
+        Ok(entity_type.is_none_or(|t| *t == entity.entity_type))
+    }
+
+    fn path_allowed(&mut self, pattern: &GraphPattern, path: &PartialPath) -> OpenFangResult<bool> {
+        for (i, edge) in path.edges.iter().enumerate() {
+            if !self.hop_allowed(pattern, i, edge, &path.nodes[i + 1])? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Run the traversal for a pattern and return the requested page of
+    /// matching paths.
+    fn find_paths(&mut self, pattern: &GraphPattern) -> OpenFangResult<Vec<PartialPath>> {
+        let max_depth = clamp_depth(pattern.max_depth) as usize;
+        let mut found = match (&pattern.source, &pattern.target) {
+            (None, Some(target)) => self.walk_backward(pattern, target, max_depth)?,
+            (source, target) => self.walk_forward(pattern, source.as_deref(), target, max_depth)?,
+        };
+        // Stable sort keeps BFS order among equal keys.
+        found.sort_by(|a, b| {
+            a.edges
+                .len()
+                .cmp(&b.edges.len())
+                .then_with(|| b.confidence().total_cmp(&a.confidence()))
+        });
+        let limit = if pattern.limit == 0 {
+            DEFAULT_GRAPH_LIMIT
+        } else {
+            pattern.limit
+        };
+        Ok(found.into_iter().skip(pattern.offset).take(limit).collect())
+    }
+
+    /// Expand paths along relation direction from the source (or from every
+    /// relation when no source is given), checking hop filters as we go.
+    fn walk_forward(
+        &mut self,
+        pattern: &GraphPattern,
+        source: Option<&str>,
+        target: &Option<String>,
+        max_depth: usize,
+    ) -> OpenFangResult<Vec<PartialPath>> {
+        let goals = match target {
+            Some(t) => Some(self.resolve(t)?.into_iter().collect::<HashSet<_>>()),
+            None => None,
+        };
+        let mut frontier: Vec<PartialPath> = Vec::new();
+        let mut found = Vec::new();
+        let mut expanded = 0usize;
+
+        match source {
+            Some(source) => {
+                for id in self.resolve(source)? {
+                    frontier.push(PartialPath {
+                        nodes: vec![id],
+                        edges: Vec::new(),
+                    });
+                }
+            }
+            None => {
+                // Seed with every first hop; the relation filter is pushed
+                // into SQL when it's known up front.
+                let first_relation = pattern
+                    .hops
+                    .first()
+                    .and_then(|h| h.relation.as_ref())
+                    .or(pattern.relation.as_ref());
+                let edges = match first_relation {
+                    Some(r) => {
+                        let rel_str = serde_json::to_string(r)
+                            .map_err(|e| OpenFangError::Serialization(e.to_string()))?;
+                        self.query_edges("relation_type = ?2", &[&rel_str])?
+                    }
+                    None => self.query_edges("1=1", &[])?,
+                };
+                for edge in edges {
+                    if edge.source == edge.target
+                        || self.entity(&edge.source)?.is_none()
+                        || !self.hop_allowed(pattern, 0, &edge, &edge.target.clone())?
+                    {
+                        continue;
+                    }
+                    let path = PartialPath {
+                        nodes: vec![edge.source.clone(), edge.target.clone()],
+                        edges: vec![edge],
+                    };
+                    if goals.as_ref().is_none_or(|g| g.contains(&path.nodes[1])) {
+                        found.push(path.clone());
+                    }
+                    expanded += 1;
+                    frontier.push(path);
+                }
+            }
+        }
+
+        while !frontier.is_empty() && expanded < MAX_EXPANDED_PATHS {
+            let mut next = Vec::new();
+            for path in &frontier {
+                if path.edges.len() >= max_depth {
+                    continue;
+                }
+                let last = path.nodes.last().cloned().unwrap_or_default();
+                for edge in self.outgoing(&last)? {
+                    if path.nodes.contains(&edge.target)
+                        || !self.hop_allowed(pattern, path.edges.len(), &edge, &edge.target)?
+                    {
+                        continue;
+                    }
+                    let mut extended = path.clone();
+                    extended.nodes.push(edge.target.clone());
+                    extended.edges.push(edge);
+                    if goals
+                        .as_ref()
+                        .is_none_or(|g| g.contains(extended.nodes.last().unwrap()))
+                    {
+                        found.push(extended.clone());
+                    }
+                    expanded += 1;
+                    if expanded >= MAX_EXPANDED_PATHS {
+                        break;
+                    }
+                    next.push(extended);
+                }
+            }
+            frontier = next;
+        }
+        Ok(found)
+    }
+
+    /// Expand paths against relation direction from the target. Hop filters
+    /// are indexed from the (not yet known) start of the path, so they are
+    /// checked once each candidate path is complete.
+    fn walk_backward(
+        &mut self,
+        pattern: &GraphPattern,
+        target: &str,
+        max_depth: usize,
+    ) -> OpenFangResult<Vec<PartialPath>> {
+        let uniform_relation = if pattern.hops.is_empty() {
+            pattern.relation.clone()
+        } else {
+            None
+        };
+        // Paths are built reversed here: nodes[0] is the target.
+        let mut frontier: Vec<PartialPath> = self
+            .resolve(target)?
+            .into_iter()
+            .map(|id| PartialPath {
+                nodes: vec![id],
+                edges: Vec::new(),
+            })
+            .collect();
+        let mut found = Vec::new();
+        let mut expanded = 0usize;
+
+        for _ in 0..max_depth {
+            let mut next = Vec::new();
+            for path in &frontier {
+                let first = path.nodes.last().cloned().unwrap_or_default();
+                for edge in self.incoming(&first)? {
+                    if path.nodes.contains(&edge.source)
+                        || self.entity(&edge.source)?.is_none()
+                        || uniform_relation
+                            .as_ref()
+                            .is_some_and(|r| *r != edge.relation.relation)
+                    {
+                        continue;
+                    }
+                    let mut extended = path.clone();
+                    extended.nodes.push(edge.source.clone());
+                    extended.edges.push(edge);
+
+                    let mut forward = extended.clone();
+                    forward.nodes.reverse();
+                    forward.edges.reverse();
+                    if self.path_allowed(pattern, &forward)? {
+                        found.push(forward);
+                    }
+                    expanded += 1;
+                    if expanded >= MAX_EXPANDED_PATHS {
+                        return Ok(found);
+                    }
+                    next.push(extended);
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+        Ok(found)
+    }
+
+    fn build_path(
+        &mut self,
+        nodes: &[String],
+        edges: &[Edge],
+    ) -> OpenFangResult<Option<GraphPath>> {
+        let mut entities = Vec::with_capacity(nodes.len());
+        for id in nodes {
+            match self.entity(id)? {
+                Some(e) => entities.push(e),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(GraphPath {
+            entities,
+            relations: edges.iter().map(|e| e.relation.clone()).collect(),
+            confidence: edges.iter().map(|e| e.relation.confidence).product(),
+        }))
     }
 }
 
-/// Raw row from a graph query.
-struct RawGraphRow {
-    s_id: String,
-    s_type: String,
-    s_name: String,
-    s_props: String,
-    s_created: String,
-    s_updated: String,
-    r_id: String,
-    r_source: String,
-    r_type: String,
-    r_target: String,
-    r_props: String,
-    r_confidence: f64,
-    r_created: String,
-    t_id: String,
-    t_type: String,
-    t_name: String,
-    t_props: String,
-    t_created: String,
-    t_updated: String,
+fn entity_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Entity> {
+    let id: String = row.get(0)?;
+    let etype: String = row.get(1)?;
+    let name: String = row.get(2)?;
+    let props: String = row.get(3)?;
+    let created: String = row.get(4)?;
+    let updated: String = row.get(5)?;
+    Ok(parse_entity(&id, &etype, &name, &props, &created, &updated))
 }
 
-// Suppress the unused field warning — r_id is part of the schema
-impl RawGraphRow {
-    #[allow(dead_code)]
-    fn relation_id(&self) -> &str {
-        &self.r_id
-    }
+fn edge_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Edge> {
+    let id: String = row.get(0)?;
+    let source: String = row.get(1)?;
+    let rtype: String = row.get(2)?;
+    let target: String = row.get(3)?;
+    let props: String = row.get(4)?;
+    let confidence: f64 = row.get(5)?;
+    let created: String = row.get(6)?;
+    Ok(Edge {
+        relation: parse_relation(&source, &rtype, &target, &props, confidence, &created),
+        id,
+        source,
+        target,
+    })
 }
 
 fn parse_entity(
@@ -275,6 +773,7 @@ fn parse_relation(
 mod tests {
     use super::*;
     use crate::migration::run_migrations;
+    use openfang_types::memory::HopFilter;
 
     fn setup() -> KnowledgeStore {
         let conn = Connection::open_in_memory().unwrap();
@@ -338,9 +837,181 @@ mod tests {
                 relation: Some(RelationType::WorksAt),
                 target: None,
                 max_depth: 1,
+                ..Default::default()
             })
             .unwrap();
         assert_eq!(matches.len(), 1);
         assert_eq!(matches[0].target.name, "Acme Corp");
     }
+
+    fn entity(store: &KnowledgeStore, id: &str, entity_type: EntityType) {
+        store
+            .add_entity(Entity {
+                id: id.to_string(),
+                entity_type,
+                name: id.to_string(),
+                properties: HashMap::new(),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            })
+            .unwrap();
+    }
+
+    fn relate(store: &KnowledgeStore, source: &str, relation: RelationType, target: &str, c: f32) {
+        store
+            .add_relation(Relation {
+                source: source.to_string(),
+                relation,
+                target: target.to_string(),
+                properties: HashMap::new(),
+                confidence: c,
+                created_at: Utc::now(),
+            })
+            .unwrap();
+    }
+
+    /// alice -works_at-> apollo -uses-> rust
+    /// bob   -works_at-> hermes -uses-> rust
+    /// carol -works_at-> zeus   -uses-> go
+    /// rust  -related_to-> apollo (cycle back)
+    fn project_graph() -> KnowledgeStore {
+        let store = setup();
+        for p in ["alice", "bob", "carol"] {
+            entity(&store, p, EntityType::Person);
+        }
+        for p in ["apollo", "hermes", "zeus"] {
+            entity(&store, p, EntityType::Project);
+        }
+        entity(&store, "rust", EntityType::Tool);
+        entity(&store, "go", EntityType::Tool);
+        relate(&store, "alice", RelationType::WorksAt, "apollo", 0.9);
+        relate(&store, "bob", RelationType::WorksAt, "hermes", 0.4);
+        relate(&store, "carol", RelationType::WorksAt, "zeus", 0.9);
+        relate(&store, "apollo", RelationType::Uses, "rust", 1.0);
+        relate(&store, "hermes", RelationType::Uses, "rust", 1.0);
+        relate(&store, "zeus", RelationType::Uses, "go", 1.0);
+        relate(&store, "rust", RelationType::RelatedTo, "apollo", 0.5);
+        store
+    }
+
+    #[test]
+    fn test_traverse_multi_hop_with_hop_filters() {
+        let store = project_graph();
+        // Who works on projects that use rust?
+        let paths = store
+            .traverse(GraphPattern {
+                target: Some("rust".to_string()),
+                max_depth: 2,
+                hops: vec![
+                    HopFilter {
+                        relation: Some(RelationType::WorksAt),
+                        entity_type: Some(EntityType::Project),
+                    },
+                    HopFilter {
+                        relation: Some(RelationType::Uses),
+                        entity_type: None,
+                    },
+                ],
+                ..Default::default()
+            })
+            .unwrap();
+        let people: Vec<&str> = paths.iter().map(|p| p.entities[0].name.as_str()).collect();
+        assert_eq!(people, vec!["alice", "bob"]); // ordered by confidence
+        assert!(paths.iter().all(|p| p.len() == 2));
+
+        // Confidence floor drops bob's low-confidence relation.
+        let paths = store
+            .traverse(GraphPattern {
+                target: Some("rust".to_string()),
+                max_depth: 2,
+                entity_type: Some(EntityType::Project),
+                hops: vec![
+                    HopFilter::default(),
+                    HopFilter {
+                        relation: Some(RelationType::Uses),
+                        entity_type: Some(EntityType::Tool),
+                    },
+                ],
+                min_confidence: 0.5,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].entities[0].name, "alice");
+    }
+
+    #[test]
+    fn test_traverse_respects_depth_cycles_and_pagination() {
+        let store = project_graph();
+        let pattern = GraphPattern {
+            source: Some("alice".to_string()),
+            max_depth: 5,
+            ..Default::default()
+        };
+        let paths = store.traverse(pattern.clone()).unwrap();
+        // alice->apollo, alice->apollo->rust; the rust->apollo edge would
+        // revisit apollo and is not followed.
+        assert_eq!(paths.len(), 2);
+        assert!(paths.iter().all(|p| {
+            let mut ids: Vec<&str> = p.entities.iter().map(|e| e.id.as_str()).collect();
+            ids.sort();
+            ids.dedup();
+            ids.len() == p.entities.len()
+        }));
+
+        let depth_one = store
+            .query_graph(GraphPattern {
+                max_depth: 1,
+                ..pattern.clone()
+            })
+            .unwrap();
+        assert_eq!(depth_one.len(), 1);
+        let depth_two = store.query_graph(pattern.clone()).unwrap();
+        assert_eq!(depth_two.len(), 2);
+
+        let page = store
+            .traverse(GraphPattern {
+                limit: 1,
+                offset: 1,
+                ..pattern
+            })
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].len(), 2);
+    }
+
+    #[test]
+    fn test_shortest_path_and_neighborhood() {
+        let store = project_graph();
+        // alice -> apollo -> rust <- hermes <- bob, following edges both ways.
+        let path = store
+            .shortest_path("alice", "bob", 6, None, 0.0)
+            .unwrap()
+            .unwrap();
+        let names: Vec<&str> = path.entities.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["alice", "apollo", "rust", "hermes", "bob"]);
+        assert_eq!(path.len(), 4);
+
+        assert!(store
+            .shortest_path("alice", "bob", 3, None, 0.0)
+            .unwrap()
+            .is_none());
+        assert!(store
+            .shortest_path("alice", "carol", 6, None, 0.0)
+            .unwrap()
+            .is_none());
+
+        let n = store
+            .neighborhood("apollo", 1, None, 0.0, 0)
+            .unwrap()
+            .unwrap();
+        let mut names: Vec<&str> = n.entities.iter().map(|(e, _)| e.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["alice", "rust"]);
+        assert_eq!(n.relations.len(), 3); // works_at, uses, related_to
+        assert!(store
+            .neighborhood("missing", 1, None, 0.0, 0)
+            .unwrap()
+            .is_none());
+    }
 }
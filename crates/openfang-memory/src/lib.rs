@@ -2,7 +2,7 @@
 //!
 //! Provides a unified memory API over three storage backends:
 //! - **Structured store** (SQLite): Key-value pairs, sessions, agent state
-//! - **Semantic store**: Text-based search (Phase 1: LIKE matching, Phase 2: Qdrant vectors)
+//! - **Semantic store**: Hybrid search (HNSW vector index + FTS5 BM25, LIKE fallback)
 //! - **Knowledge graph** (SQLite): Entities and relations
 //!
 //! Agents interact with a single `Memory` trait that abstracts over all three stores.
@@ -12,10 +12,12 @@ pub mod consolidation;
 pub mod http_client;
 pub mod knowledge;
 pub mod migration;
+pub mod portability;
 pub mod semantic;
 pub mod session;
 pub mod structured;
 pub mod usage;
+pub mod vector_index;
 
 mod substrate;
 pub use substrate::MemorySubstrate;
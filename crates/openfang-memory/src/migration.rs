@@ -2,10 +2,12 @@
 //!
 //! Creates all tables needed by the memory substrate on first boot.
 
+use crate::session::{index_canonical_messages, index_session_messages};
+use openfang_types::message::Message;
 use rusqlite::Connection;
 
 /// Current schema version.
-const SCHEMA_VERSION: u32 = 8;
+const SCHEMA_VERSION: u32 = 12;
 
 /// Run all migrations to bring the database up to date.
 pub fn run_migrations(conn: &Connection) -> Result<(), rusqlite::Error> {
@@ -43,6 +45,22 @@ pub fn run_migrations(conn: &Connection) -> Result<(), rusqlite::Error> {
         migrate_v8(conn)?;
     }
 
+    if current_version < 9 {
+        migrate_v9(conn)?;
+    }
+
+    if current_version < 10 {
+        migrate_v10(conn)?;
+    }
+
+    if current_version < 11 {
+        migrate_v11(conn)?;
+    }
+
+    if current_version < 12 {
+        migrate_v12(conn)?;
+    }
+
     set_schema_version(conn, SCHEMA_VERSION)?;
     Ok(())
 }
@@ -328,6 +346,148 @@ fn migrate_v8(conn: &Connection) -> Result<(), rusqlite::Error> {
     Ok(())
 }
 
+/// Version 9: Add merged_into column to memories for consolidation provenance.
+fn migrate_v9(conn: &Connection) -> Result<(), rusqlite::Error> {
+    if !column_exists(conn, "memories", "merged_into") {
+        conn.execute(
+            "ALTER TABLE memories ADD COLUMN merged_into TEXT DEFAULT NULL",
+            [],
+        )?;
+    }
+    conn.execute(
+        "INSERT OR IGNORE INTO migrations (version, applied_at, description) VALUES (9, datetime('now'), 'Add merged_into column to memories for consolidation provenance')",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Version 10: Index memory creation time for time-bounded recall.
+fn migrate_v10(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_memories_agent_created ON memories(agent_id, created_at);",
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO migrations (version, applied_at, description) VALUES (10, datetime('now'), 'Add agent/created_at index on memories for time-bounded recall')",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Version 11: FTS5 keyword index over memory content for hybrid recall.
+///
+/// External-content table kept in sync by triggers, so memory text is
+/// stored once.
+fn migrate_v11(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "
+        CREATE VIRTUAL TABLE IF NOT EXISTS memories_fts USING fts5(
+            content,
+            content='memories',
+            content_rowid='rowid'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS memories_fts_ai AFTER INSERT ON memories BEGIN
+            INSERT INTO memories_fts(rowid, content) VALUES (new.rowid, new.content);
+        END;
+        CREATE TRIGGER IF NOT EXISTS memories_fts_ad AFTER DELETE ON memories BEGIN
+            INSERT INTO memories_fts(memories_fts, rowid, content)
+                VALUES ('delete', old.rowid, old.content);
+        END;
+        CREATE TRIGGER IF NOT EXISTS memories_fts_au AFTER UPDATE OF content ON memories BEGIN
+            INSERT INTO memories_fts(memories_fts, rowid, content)
+                VALUES ('delete', old.rowid, old.content);
+            INSERT INTO memories_fts(rowid, content) VALUES (new.rowid, new.content);
+        END;
+
+        INSERT INTO memories_fts(memories_fts) VALUES ('rebuild');
+        ",
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO migrations (version, applied_at, description) VALUES (11, datetime('now'), 'Add memories_fts keyword index for hybrid recall')",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Version 12: Full-text index over session transcripts.
+///
+/// Messages are stored as msgpack blobs, so the searchable text lives in
+/// `session_messages` (maintained by the session store) with an FTS5
+/// external-content index on top. Existing transcripts are backfilled.
+fn migrate_v12(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS session_messages (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id TEXT,
+            agent_id TEXT NOT NULL,
+            source TEXT NOT NULL,
+            msg_index INTEGER NOT NULL,
+            role TEXT NOT NULL,
+            content TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_session_messages_session
+            ON session_messages(session_id, msg_index);
+        CREATE INDEX IF NOT EXISTS idx_session_messages_agent
+            ON session_messages(agent_id, source, created_at);
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS session_messages_fts USING fts5(
+            content,
+            content='session_messages',
+            content_rowid='id'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS session_messages_fts_ai AFTER INSERT ON session_messages BEGIN
+            INSERT INTO session_messages_fts(rowid, content) VALUES (new.id, new.content);
+        END;
+        CREATE TRIGGER IF NOT EXISTS session_messages_fts_ad AFTER DELETE ON session_messages BEGIN
+            INSERT INTO session_messages_fts(session_messages_fts, rowid, content)
+                VALUES ('delete', old.id, old.content);
+        END;
+        CREATE TRIGGER IF NOT EXISTS session_messages_fts_au AFTER UPDATE OF content ON session_messages BEGIN
+            INSERT INTO session_messages_fts(session_messages_fts, rowid, content)
+                VALUES ('delete', old.id, old.content);
+            INSERT INTO session_messages_fts(rowid, content) VALUES (new.id, new.content);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS sessions_search_ad AFTER DELETE ON sessions BEGIN
+            DELETE FROM session_messages WHERE source = 'session' AND session_id = old.id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS canonical_sessions_search_ad AFTER DELETE ON canonical_sessions BEGIN
+            DELETE FROM session_messages WHERE source = 'canonical' AND agent_id = old.agent_id;
+        END;
+        ",
+    )?;
+
+    let sessions: Vec<(String, String, Vec<u8>, String)> = conn
+        .prepare("SELECT id, agent_id, messages, updated_at FROM sessions")?
+        .query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .collect::<Result<_, _>>()?;
+    for (id, agent_id, blob, updated_at) in sessions {
+        if let Ok(messages) = rmp_serde::from_slice::<Vec<Message>>(&blob) {
+            index_session_messages(conn, &id, &agent_id, &messages, &updated_at)?;
+        }
+    }
+    let canonical: Vec<(String, Vec<u8>, String)> = conn
+        .prepare("SELECT agent_id, messages, updated_at FROM canonical_sessions")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<_, _>>()?;
+    for (agent_id, blob, updated_at) in canonical {
+        if let Ok(messages) = rmp_serde::from_slice::<Vec<Message>>(&blob) {
+            index_canonical_messages(conn, &agent_id, &messages, &updated_at)?;
+        }
+    }
+
+    conn.execute(
+        "INSERT OR IGNORE INTO migrations (version, applied_at, description) VALUES (12, datetime('now'), 'Add session_messages full-text index over session transcripts')",
+        [],
+    )?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,911 @@
+//! Memory export/import for agent-memory portability and backups.
+//!
+//! A [`MemorySnapshot`] captures KV pairs, semantic memories (with embeddings),
+//! the knowledge graph, and optionally sessions. Snapshots serialize to JSON or
+//! MessagePack and can be imported into another substrate with merge or
+//! replace semantics. Import is best-effort per record: a bad record is
+//! reported in [`ImportReport::errors`] and the rest still land.
+
+use crate::session::{index_canonical_messages, index_session_messages};
+use chrono::Utc;
+use openfang_types::agent::AgentId;
+use openfang_types::error::{OpenFangError, OpenFangResult};
+use openfang_types::memory::{
+    Entity, EntityType, ExportFormat, ImportMode, ImportReport, MemoryFragment, MemoryId,
+    MemorySource, Relation, RelationType,
+};
+use openfang_types::message::Message;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// Current snapshot format version. Bump when the layout changes incompatibly.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Options controlling what goes into an export.
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    /// Only export data owned by this agent. The knowledge graph is global
+    /// and is always exported in full.
+    pub agent_id: Option<AgentId>,
+    /// Include regular and canonical sessions.
+    pub include_sessions: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            agent_id: None,
+            include_sessions: true,
+        }
+    }
+}
+
+/// A single key-value pair in a snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KvRecord {
+    /// Owning agent.
+    pub agent_id: AgentId,
+    /// Key name.
+    pub key: String,
+    /// Stored value.
+    pub value: serde_json::Value,
+}
+
+/// A knowledge-graph relation with its row ID, so re-imports stay idempotent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationRecord {
+    /// Relation row ID.
+    pub id: String,
+    /// The relation itself.
+    pub relation: Relation,
+}
+
+/// A conversation session in a snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    /// Session ID.
+    pub id: String,
+    /// Owning agent.
+    pub agent_id: AgentId,
+    /// Conversation messages.
+    pub messages: Vec<Message>,
+    /// Estimated token count for the context window.
+    pub context_window_tokens: u64,
+    /// Optional human-readable label.
+    pub label: Option<String>,
+    /// Creation time (RFC 3339).
+    pub created_at: String,
+    /// Last update time (RFC 3339).
+    pub updated_at: String,
+}
+
+/// A canonical (cross-channel) session in a snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanonicalSessionRecord {
+    /// Owning agent.
+    pub agent_id: AgentId,
+    /// Message window.
+    pub messages: Vec<Message>,
+    /// Compaction cursor.
+    pub compaction_cursor: u64,
+    /// Summary of compacted messages.
+    pub compacted_summary: Option<String>,
+    /// Last update time (RFC 3339).
+    pub updated_at: String,
+}
+
+/// Portable snapshot of a memory substrate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemorySnapshot {
+    /// Snapshot format version.
+    pub version: u32,
+    /// When the snapshot was taken (RFC 3339).
+    pub exported_at: String,
+    /// Agent filter the snapshot was taken with, if any.
+    #[serde(default)]
+    pub agent_id: Option<AgentId>,
+    /// Key-value pairs.
+    #[serde(default)]
+    pub kv: Vec<KvRecord>,
+    /// Semantic memories (soft-deleted rows are excluded).
+    #[serde(default)]
+    pub memories: Vec<MemoryFragment>,
+    /// Knowledge-graph entities.
+    #[serde(default)]
+    pub entities: Vec<Entity>,
+    /// Knowledge-graph relations.
+    #[serde(default)]
+    pub relations: Vec<RelationRecord>,
+    /// Regular sessions (empty when exported without sessions).
+    #[serde(default)]
+    pub sessions: Vec<SessionRecord>,
+    /// Canonical sessions (empty when exported without sessions).
+    #[serde(default)]
+    pub canonical_sessions: Vec<CanonicalSessionRecord>,
+}
+
+impl MemorySnapshot {
+    /// Serialize the snapshot in the given format.
+    pub fn encode(&self, format: ExportFormat) -> OpenFangResult<Vec<u8>> {
+        match format {
+            ExportFormat::Json => serde_json::to_vec_pretty(self)
+                .map_err(|e| OpenFangError::Serialization(e.to_string())),
+            ExportFormat::MessagePack => rmp_serde::to_vec_named(self)
+                .map_err(|e| OpenFangError::Serialization(e.to_string())),
+        }
+    }
+
+    /// Deserialize a snapshot in the given format.
+    pub fn decode(data: &[u8], format: ExportFormat) -> OpenFangResult<Self> {
+        let snapshot: Self = match format {
+            ExportFormat::Json => serde_json::from_slice(data)
+                .map_err(|e| OpenFangError::Serialization(e.to_string()))?,
+            ExportFormat::MessagePack => rmp_serde::from_slice(data)
+                .map_err(|e| OpenFangError::Serialization(e.to_string()))?,
+        };
+        if snapshot.version > SNAPSHOT_VERSION {
+            return Err(OpenFangError::Memory(format!(
+                "Snapshot version {} is newer than supported version {SNAPSHOT_VERSION}",
+                snapshot.version
+            )));
+        }
+        Ok(snapshot)
+    }
+}
+
+/// Export/import over the shared SQLite connection.
+#[derive(Clone)]
+pub struct PortabilityStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl PortabilityStore {
+    /// Create a new portability store wrapping the given connection.
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+
+    /// Take a snapshot of the store.
+    pub fn export(&self, options: &ExportOptions) -> OpenFangResult<MemorySnapshot> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?;
+        let agent = options.agent_id.map(|a| a.0.to_string());
+
+        let (sessions, canonical_sessions) = if options.include_sessions {
+            (
+                export_sessions(&conn, agent.as_deref())?,
+                export_canonical_sessions(&conn, agent.as_deref())?,
+            )
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        Ok(MemorySnapshot {
+            version: SNAPSHOT_VERSION,
+            exported_at: Utc::now().to_rfc3339(),
+            agent_id: options.agent_id,
+            kv: export_kv(&conn, agent.as_deref())?,
+            memories: export_memories(&conn, agent.as_deref())?,
+            entities: export_entities(&conn)?,
+            relations: export_relations(&conn)?,
+            sessions,
+            canonical_sessions,
+        })
+    }
+
+    /// Import a snapshot.
+    ///
+    /// In [`ImportMode::Replace`], data owned by the agents present in the
+    /// snapshot is cleared first, and the knowledge graph is cleared when the
+    /// snapshot carries one. Everything runs in a single transaction; per-record
+    /// failures are collected rather than aborting the import.
+    pub fn import(
+        &self,
+        snapshot: &MemorySnapshot,
+        mode: ImportMode,
+    ) -> OpenFangResult<ImportReport> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        let mut report = ImportReport::default();
+
+        if mode == ImportMode::Replace {
+            clear_for_replace(&tx, snapshot).map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        }
+
+        for kv in &snapshot.kv {
+            match import_kv(&tx, kv) {
+                Ok(()) => report.kv_imported += 1,
+                Err(e) => report
+                    .errors
+                    .push(format!("kv {}/{}: {e}", kv.agent_id, kv.key)),
+            }
+        }
+        for frag in &snapshot.memories {
+            match import_memory(&tx, frag) {
+                Ok(()) => report.memories_imported += 1,
+                Err(e) => report.errors.push(format!("memory {}: {e}", frag.id)),
+            }
+        }
+        for entity in &snapshot.entities {
+            match import_entity(&tx, entity) {
+                Ok(()) => report.entities_imported += 1,
+                Err(e) => report.errors.push(format!("entity {}: {e}", entity.id)),
+            }
+        }
+        for rel in &snapshot.relations {
+            match import_relation(&tx, rel) {
+                Ok(()) => report.relations_imported += 1,
+                Err(e) => report.errors.push(format!("relation {}: {e}", rel.id)),
+            }
+        }
+        for session in &snapshot.sessions {
+            match import_session(&tx, session) {
+                Ok(()) => report.sessions_imported += 1,
+                Err(e) => report.errors.push(format!("session {}: {e}", session.id)),
+            }
+        }
+        for canonical in &snapshot.canonical_sessions {
+            match import_canonical_session(&tx, canonical) {
+                Ok(()) => report.sessions_imported += 1,
+                Err(e) => report
+                    .errors
+                    .push(format!("canonical session {}: {e}", canonical.agent_id)),
+            }
+        }
+
+        tx.commit()
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        Ok(report)
+    }
+}
+
+/// Per-record import error (stringified into the report).
+type RecordResult = Result<(), String>;
+
+fn parse_agent_id(s: &str) -> rusqlite::Result<AgentId> {
+    uuid::Uuid::parse_str(s).map(AgentId).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+fn parse_time(s: &str) -> chrono::DateTime<Utc> {
+    chrono::DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+/// Build `" WHERE agent_id = ?1"` (or `" AND ..."`) plus its params for an optional agent filter.
+fn agent_clause(agent: Option<&str>, prefix: &str) -> (String, Vec<String>) {
+    match agent {
+        Some(a) => (format!(" {prefix} agent_id = ?1"), vec![a.to_string()]),
+        None => (String::new(), Vec::new()),
+    }
+}
+
+fn export_kv(conn: &Connection, agent: Option<&str>) -> OpenFangResult<Vec<KvRecord>> {
+    let (clause, params) = agent_clause(agent, "WHERE");
+    let sql = format!("SELECT agent_id, key, value FROM kv_store{clause} ORDER BY agent_id, key");
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(params.iter()), |row| {
+            let agent_str: String = row.get(0)?;
+            let key: String = row.get(1)?;
+            let blob: Vec<u8> = row.get(2)?;
+            Ok((parse_agent_id(&agent_str)?, key, blob))
+        })
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        let (agent_id, key, blob) = row.map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        let value = serde_json::from_slice(&blob).unwrap_or_else(|_| {
+            String::from_utf8(blob)
+                .map(serde_json::Value::String)
+                .unwrap_or(serde_json::Value::Null)
+        });
+        out.push(KvRecord {
+            agent_id,
+            key,
+            value,
+        });
+    }
+    Ok(out)
+}
+
+fn export_memories(conn: &Connection, agent: Option<&str>) -> OpenFangResult<Vec<MemoryFragment>> {
+    let (clause, params) = agent_clause(agent, "AND");
+    let sql = format!(
+        "SELECT id, agent_id, content, source, scope, confidence, metadata, created_at, accessed_at, access_count, embedding
+         FROM memories WHERE deleted = 0{clause} ORDER BY created_at"
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(params.iter()), |row| {
+            let id_str: String = row.get(0)?;
+            let agent_str: String = row.get(1)?;
+            let source_str: String = row.get(3)?;
+            let meta_str: String = row.get(6)?;
+            let created_str: String = row.get(7)?;
+            let accessed_str: String = row.get(8)?;
+            let embedding_bytes: Option<Vec<u8>> = row.get(10)?;
+            Ok(MemoryFragment {
+                id: uuid::Uuid::parse_str(&id_str).map(MemoryId).map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                        0,
+                        rusqlite::types::Type::Text,
+                        Box::new(e),
+                    )
+                })?,
+                agent_id: parse_agent_id(&agent_str)?,
+                content: row.get(2)?,
+                embedding: embedding_bytes.as_deref().map(embedding_from_bytes),
+                metadata: serde_json::from_str(&meta_str).unwrap_or_default(),
+                source: serde_json::from_str(&source_str).unwrap_or(MemorySource::System),
+                confidence: row.get::<_, f64>(5)? as f32,
+                created_at: parse_time(&created_str),
+                accessed_at: parse_time(&accessed_str),
+                access_count: row.get::<_, i64>(9)? as u64,
+                scope: row.get(4)?,
+            })
+        })
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| OpenFangError::Memory(e.to_string()))
+}
+
+fn export_entities(conn: &Connection) -> OpenFangResult<Vec<Entity>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, entity_type, name, properties, created_at, updated_at FROM entities ORDER BY id",
+        )
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+    let rows = stmt
+        .query_map([], |row| {
+            let etype: String = row.get(1)?;
+            let props: String = row.get(3)?;
+            let created: String = row.get(4)?;
+            let updated: String = row.get(5)?;
+            Ok(Entity {
+                id: row.get(0)?,
+                entity_type: serde_json::from_str(&etype)
+                    .unwrap_or(EntityType::Custom("unknown".to_string())),
+                name: row.get(2)?,
+                properties: serde_json::from_str(&props).unwrap_or_default(),
+                created_at: parse_time(&created),
+                updated_at: parse_time(&updated),
+            })
+        })
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| OpenFangError::Memory(e.to_string()))
+}
+
+fn export_relations(conn: &Connection) -> OpenFangResult<Vec<RelationRecord>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, source_entity, relation_type, target_entity, properties, confidence, created_at
+             FROM relations ORDER BY created_at",
+        )
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+    let rows = stmt
+        .query_map([], |row| {
+            let rtype: String = row.get(2)?;
+            let props: String = row.get(4)?;
+            let created: String = row.get(6)?;
+            Ok(RelationRecord {
+                id: row.get(0)?,
+                relation: Relation {
+                    source: row.get(1)?,
+                    relation: serde_json::from_str(&rtype).unwrap_or(RelationType::RelatedTo),
+                    target: row.get(3)?,
+                    properties: serde_json::from_str::<HashMap<String, serde_json::Value>>(&props)
+                        .unwrap_or_default(),
+                    confidence: row.get::<_, f64>(5)? as f32,
+                    created_at: parse_time(&created),
+                },
+            })
+        })
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| OpenFangError::Memory(e.to_string()))
+}
+
+fn export_sessions(conn: &Connection, agent: Option<&str>) -> OpenFangResult<Vec<SessionRecord>> {
+    let (clause, params) = agent_clause(agent, "WHERE");
+    let sql = format!(
+        "SELECT id, agent_id, messages, context_window_tokens, label, created_at, updated_at
+         FROM sessions{clause} ORDER BY created_at"
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(params.iter()), |row| {
+            let agent_str: String = row.get(1)?;
+            let blob: Vec<u8> = row.get(2)?;
+            Ok((
+                row.get::<_, String>(0)?,
+                parse_agent_id(&agent_str)?,
+                blob,
+                row.get::<_, i64>(3)?,
+                row.get::<_, Option<String>>(4).unwrap_or(None),
+                row.get::<_, String>(5)?,
+                row.get::<_, String>(6)?,
+            ))
+        })
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        let (id, agent_id, blob, tokens, label, created_at, updated_at) =
+            row.map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        let messages: Vec<Message> = rmp_serde::from_slice(&blob)
+            .map_err(|e| OpenFangError::Serialization(format!("session {id}: {e}")))?;
+        out.push(SessionRecord {
+            id,
+            agent_id,
+            messages,
+            context_window_tokens: tokens as u64,
+            label,
+            created_at,
+            updated_at,
+        });
+    }
+    Ok(out)
+}
+
+fn export_canonical_sessions(
+    conn: &Connection,
+    agent: Option<&str>,
+) -> OpenFangResult<Vec<CanonicalSessionRecord>> {
+    let (clause, params) = agent_clause(agent, "WHERE");
+    let sql = format!(
+        "SELECT agent_id, messages, compaction_cursor, compacted_summary, updated_at
+         FROM canonical_sessions{clause} ORDER BY agent_id"
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(params.iter()), |row| {
+            let agent_str: String = row.get(0)?;
+            let blob: Vec<u8> = row.get(1)?;
+            Ok((
+                parse_agent_id(&agent_str)?,
+                blob,
+                row.get::<_, i64>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        let (agent_id, blob, cursor, compacted_summary, updated_at) =
+            row.map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        let messages: Vec<Message> = rmp_serde::from_slice(&blob).map_err(|e| {
+            OpenFangError::Serialization(format!("canonical session {agent_id}: {e}"))
+        })?;
+        out.push(CanonicalSessionRecord {
+            agent_id,
+            messages,
+            compaction_cursor: cursor as u64,
+            compacted_summary,
+            updated_at,
+        });
+    }
+    Ok(out)
+}
+
+/// Clear the data a replace-mode import is about to overwrite.
+fn clear_for_replace(conn: &Connection, snapshot: &MemorySnapshot) -> rusqlite::Result<()> {
+    let mut agents: BTreeSet<String> = BTreeSet::new();
+    if let Some(a) = snapshot.agent_id {
+        agents.insert(a.0.to_string());
+    }
+    agents.extend(snapshot.kv.iter().map(|r| r.agent_id.0.to_string()));
+    agents.extend(snapshot.memories.iter().map(|r| r.agent_id.0.to_string()));
+    agents.extend(snapshot.sessions.iter().map(|r| r.agent_id.0.to_string()));
+    agents.extend(
+        snapshot
+            .canonical_sessions
+            .iter()
+            .map(|r| r.agent_id.0.to_string()),
+    );
+
+    for agent in &agents {
+        conn.execute("DELETE FROM kv_store WHERE agent_id = ?1", [agent])?;
+        conn.execute("DELETE FROM memories WHERE agent_id = ?1", [agent])?;
+        if !snapshot.sessions.is_empty() || !snapshot.canonical_sessions.is_empty() {
+            conn.execute("DELETE FROM sessions WHERE agent_id = ?1", [agent])?;
+            conn.execute(
+                "DELETE FROM canonical_sessions WHERE agent_id = ?1",
+                [agent],
+            )?;
+        }
+    }
+    if !snapshot.entities.is_empty() || !snapshot.relations.is_empty() {
+        conn.execute("DELETE FROM relations", [])?;
+        conn.execute("DELETE FROM entities", [])?;
+    }
+    Ok(())
+}
+
+fn import_kv(conn: &Connection, kv: &KvRecord) -> RecordResult {
+    let blob = serde_json::to_vec(&kv.value).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO kv_store (agent_id, key, value, version, updated_at) VALUES (?1, ?2, ?3, 1, ?4)
+         ON CONFLICT(agent_id, key) DO UPDATE SET value = ?3, version = version + 1, updated_at = ?4",
+        rusqlite::params![kv.agent_id.0.to_string(), kv.key, blob, Utc::now().to_rfc3339()],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn import_memory(conn: &Connection, frag: &MemoryFragment) -> RecordResult {
+    if frag.content.is_empty() {
+        return Err("empty content".to_string());
+    }
+    let source_str = serde_json::to_string(&frag.source).map_err(|e| e.to_string())?;
+    let meta_str = serde_json::to_string(&frag.metadata).map_err(|e| e.to_string())?;
+    let embedding = frag.embedding.as_deref().map(embedding_to_bytes);
+    conn.execute(
+        "INSERT INTO memories (id, agent_id, content, source, scope, confidence, metadata, created_at, accessed_at, access_count, deleted, embedding)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 0, ?11)
+         ON CONFLICT(id) DO UPDATE SET agent_id = ?2, content = ?3, source = ?4, scope = ?5, confidence = ?6,
+             metadata = ?7, created_at = ?8, accessed_at = ?9, access_count = ?10, deleted = 0, embedding = ?11",
+        rusqlite::params![
+            frag.id.0.to_string(),
+            frag.agent_id.0.to_string(),
+            frag.content,
+            source_str,
+            frag.scope,
+            frag.confidence.clamp(0.0, 1.0) as f64,
+            meta_str,
+            frag.created_at.to_rfc3339(),
+            frag.accessed_at.to_rfc3339(),
+            frag.access_count as i64,
+            embedding,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn import_entity(conn: &Connection, entity: &Entity) -> RecordResult {
+    if entity.id.is_empty() {
+        return Err("missing id".to_string());
+    }
+    let etype = serde_json::to_string(&entity.entity_type).map_err(|e| e.to_string())?;
+    let props = serde_json::to_string(&entity.properties).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO entities (id, entity_type, name, properties, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(id) DO UPDATE SET entity_type = ?2, name = ?3, properties = ?4, updated_at = ?6",
+        rusqlite::params![
+            entity.id,
+            etype,
+            entity.name,
+            props,
+            entity.created_at.to_rfc3339(),
+            entity.updated_at.to_rfc3339(),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn import_relation(conn: &Connection, rec: &RelationRecord) -> RecordResult {
+    if rec.id.is_empty() {
+        return Err("missing id".to_string());
+    }
+    let rtype = serde_json::to_string(&rec.relation.relation).map_err(|e| e.to_string())?;
+    let props = serde_json::to_string(&rec.relation.properties).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO relations (id, source_entity, relation_type, target_entity, properties, confidence, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(id) DO UPDATE SET source_entity = ?2, relation_type = ?3, target_entity = ?4,
+             properties = ?5, confidence = ?6",
+        rusqlite::params![
+            rec.id,
+            rec.relation.source,
+            rtype,
+            rec.relation.target,
+            props,
+            rec.relation.confidence as f64,
+            rec.relation.created_at.to_rfc3339(),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn import_session(conn: &Connection, session: &SessionRecord) -> RecordResult {
+    uuid::Uuid::parse_str(&session.id).map_err(|e| format!("invalid id: {e}"))?;
+    let blob = rmp_serde::to_vec_named(&session.messages).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO sessions (id, agent_id, messages, context_window_tokens, label, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(id) DO UPDATE SET agent_id = ?2, messages = ?3, context_window_tokens = ?4,
+             label = ?5, updated_at = ?7",
+        rusqlite::params![
+            session.id,
+            session.agent_id.0.to_string(),
+            blob,
+            session.context_window_tokens as i64,
+            session.label,
+            session.created_at,
+            session.updated_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    index_session_messages(
+        conn,
+        &session.id,
+        &session.agent_id.0.to_string(),
+        &session.messages,
+        &session.updated_at,
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn import_canonical_session(conn: &Connection, canonical: &CanonicalSessionRecord) -> RecordResult {
+    let blob = rmp_serde::to_vec(&canonical.messages).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO canonical_sessions (agent_id, messages, compaction_cursor, compacted_summary, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(agent_id) DO UPDATE SET messages = ?2, compaction_cursor = ?3,
+             compacted_summary = ?4, updated_at = ?5",
+        rusqlite::params![
+            canonical.agent_id.0.to_string(),
+            blob,
+            canonical.compaction_cursor as i64,
+            canonical.compacted_summary,
+            canonical.updated_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    index_canonical_messages(
+        conn,
+        &canonical.agent_id.0.to_string(),
+        &canonical.messages,
+        &canonical.updated_at,
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Serialize embedding to little-endian f32 bytes (same layout as the semantic store).
+fn embedding_to_bytes(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// Deserialize embedding from little-endian f32 bytes.
+fn embedding_from_bytes(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::knowledge::KnowledgeStore;
+    use crate::migration::run_migrations;
+    use crate::semantic::SemanticStore;
+    use crate::session::SessionStore;
+    use crate::structured::StructuredStore;
+
+    struct Stores {
+        portability: PortabilityStore,
+        structured: StructuredStore,
+        semantic: SemanticStore,
+        knowledge: KnowledgeStore,
+        sessions: SessionStore,
+    }
+
+    fn setup() -> Stores {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        let shared = Arc::new(Mutex::new(conn));
+        Stores {
+            portability: PortabilityStore::new(Arc::clone(&shared)),
+            structured: StructuredStore::new(Arc::clone(&shared)),
+            semantic: SemanticStore::new(Arc::clone(&shared)),
+            knowledge: KnowledgeStore::new(Arc::clone(&shared)),
+            sessions: SessionStore::new(shared),
+        }
+    }
+
+    fn populate(stores: &Stores, agent_id: AgentId) {
+        stores
+            .structured
+            .set(agent_id, "favorite_color", serde_json::json!("teal"))
+            .unwrap();
+        stores
+            .semantic
+            .remember_with_embedding(
+                agent_id,
+                "The user prefers Rust",
+                MemorySource::Conversation,
+                "episodic",
+                HashMap::new(),
+                Some(&[0.25, 0.5, 0.75]),
+            )
+            .unwrap();
+        for (id, name) in [("alice", "Alice"), ("acme", "Acme")] {
+            stores
+                .knowledge
+                .add_entity(Entity {
+                    id: id.to_string(),
+                    entity_type: EntityType::Person,
+                    name: name.to_string(),
+                    properties: HashMap::new(),
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                })
+                .unwrap();
+        }
+        stores
+            .knowledge
+            .add_relation(Relation {
+                source: "alice".to_string(),
+                relation: RelationType::WorksAt,
+                target: "acme".to_string(),
+                properties: HashMap::new(),
+                confidence: 0.9,
+                created_at: Utc::now(),
+            })
+            .unwrap();
+        let mut session = stores.sessions.create_session(agent_id).unwrap();
+        session.messages.push(Message::user("hello"));
+        stores.sessions.save_session(&session).unwrap();
+    }
+
+    #[test]
+    fn test_round_trip_json_and_msgpack() {
+        let src = setup();
+        let agent_id = AgentId::new();
+        populate(&src, agent_id);
+        let snapshot = src.portability.export(&ExportOptions::default()).unwrap();
+        assert_eq!(snapshot.kv.len(), 1);
+        assert_eq!(snapshot.memories.len(), 1);
+        assert_eq!(snapshot.entities.len(), 2);
+        assert_eq!(snapshot.relations.len(), 1);
+        assert_eq!(snapshot.sessions.len(), 1);
+
+        for format in [ExportFormat::Json, ExportFormat::MessagePack] {
+            let bytes = snapshot.encode(format).unwrap();
+            let decoded = MemorySnapshot::decode(&bytes, format).unwrap();
+
+            let dst = setup();
+            let report = dst.portability.import(&decoded, ImportMode::Merge).unwrap();
+            assert!(report.errors.is_empty(), "{:?}", report.errors);
+            assert_eq!(report.kv_imported, 1);
+            assert_eq!(report.memories_imported, 1);
+            assert_eq!(report.entities_imported, 2);
+            assert_eq!(report.relations_imported, 1);
+            assert_eq!(report.sessions_imported, 1);
+
+            assert_eq!(
+                dst.structured.get(agent_id, "favorite_color").unwrap(),
+                Some(serde_json::json!("teal"))
+            );
+            let recalled = dst
+                .semantic
+                .recall_with_embedding("", 10, None, Some(&[0.25, 0.5, 0.75]))
+                .unwrap();
+            assert_eq!(recalled.len(), 1);
+            assert_eq!(
+                recalled[0].embedding.as_deref(),
+                Some(&[0.25, 0.5, 0.75][..])
+            );
+            let session = dst.sessions.list_agent_sessions(agent_id).unwrap();
+            assert_eq!(session.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_merge_is_idempotent_and_replace_clears() {
+        let stores = setup();
+        let agent_id = AgentId::new();
+        populate(&stores, agent_id);
+        let snapshot = stores
+            .portability
+            .export(&ExportOptions {
+                agent_id: Some(agent_id),
+                include_sessions: false,
+            })
+            .unwrap();
+        assert!(snapshot.sessions.is_empty());
+
+        // Re-importing the same snapshot must not duplicate anything.
+        stores
+            .portability
+            .import(&snapshot, ImportMode::Merge)
+            .unwrap();
+        let again = stores
+            .portability
+            .export(&ExportOptions::default())
+            .unwrap();
+        assert_eq!(again.memories.len(), 1);
+        assert_eq!(again.relations.len(), 1);
+
+        // Replace drops data that is not in the snapshot.
+        stores
+            .structured
+            .set(agent_id, "stale", serde_json::json!(1))
+            .unwrap();
+        stores
+            .portability
+            .import(&snapshot, ImportMode::Replace)
+            .unwrap();
+        assert!(stores.structured.get(agent_id, "stale").unwrap().is_none());
+        assert!(stores
+            .structured
+            .get(agent_id, "favorite_color")
+            .unwrap()
+            .is_some());
+        // Sessions were not part of the snapshot, so replace leaves them alone.
+        assert_eq!(
+            stores.sessions.list_agent_sessions(agent_id).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_import_reports_bad_records() {
+        let stores = setup();
+        let mut snapshot = stores
+            .portability
+            .export(&ExportOptions::default())
+            .unwrap();
+        snapshot.entities.push(Entity {
+            id: String::new(),
+            entity_type: EntityType::Concept,
+            name: "nameless".to_string(),
+            properties: HashMap::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        });
+        snapshot.sessions.push(SessionRecord {
+            id: "not-a-uuid".to_string(),
+            agent_id: AgentId::new(),
+            messages: Vec::new(),
+            context_window_tokens: 0,
+            label: None,
+            created_at: Utc::now().to_rfc3339(),
+            updated_at: Utc::now().to_rfc3339(),
+        });
+        let report = stores
+            .portability
+            .import(&snapshot, ImportMode::Merge)
+            .unwrap();
+        assert_eq!(report.entities_imported, 0);
+        assert_eq!(report.sessions_imported, 0);
+        assert_eq!(report.errors.len(), 2);
+    }
+
+    #[test]
+    fn test_decode_rejects_newer_version() {
+        let data = serde_json::json!({"version": SNAPSHOT_VERSION + 1, "exported_at": ""});
+        let bytes = serde_json::to_vec(&data).unwrap();
+        assert!(MemorySnapshot::decode(&bytes, ExportFormat::Json).is_err());
+    }
+}
@@ -4,20 +4,27 @@
 //! Phase 2: Vector cosine similarity search using stored embeddings.
 //!
 //! Embeddings are stored as BLOBs in the `embedding` column of the memories table.
-//! When a query embedding is provided, recall uses cosine similarity ranking.
-//! When no embeddings are available, falls back to LIKE matching.
+//! When a query embedding is provided, recall gathers candidates from the
+//! recency window, the optional HNSW index (`vector_index`) and the
+//! `memories_fts` BM25 index, then ranks them by a weighted mix of cosine
+//! similarity and keyword score. When no embeddings are available, falls back
+//! to LIKE matching.
 
+use crate::vector_index::HnswIndex;
 use chrono::Utc;
 use openfang_types::agent::AgentId;
 use openfang_types::error::{OpenFangError, OpenFangResult};
-use openfang_types::memory::{MemoryFilter, MemoryFragment, MemoryId, MemorySource};
+use openfang_types::memory::{
+    MemoryFilter, MemoryFragment, MemoryId, MemorySource, MetadataPredicate,
+};
 use rusqlite::Connection;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use tracing::{debug, warn};
 
 #[cfg(feature = "http-memory")]
-use crate::http_client::MemoryApiClient;
+use crate::http_client::{MemoryApiClient, SearchFilters};
 
 /// Semantic store backed by SQLite with optional vector search.
 ///
@@ -30,6 +37,49 @@ pub struct SemanticStore {
     conn: Arc<Mutex<Connection>>,
     #[cfg(feature = "http-memory")]
     http_client: Option<MemoryApiClient>,
+    /// Optional ANN index over embeddings, shared by all clones.
+    ann: Option<Arc<Mutex<AnnState>>>,
+    /// Weight of the BM25 keyword score in hybrid ranking (0 = vector only).
+    keyword_weight: f32,
+}
+
+/// Default weight of the keyword score in hybrid recall.
+pub const DEFAULT_KEYWORD_WEIGHT: f32 = 0.3;
+
+/// Persist the ANN index after this many unsaved changes.
+const ANN_SAVE_EVERY: usize = 64;
+
+/// The ANN index plus where (and whether) to persist it.
+struct AnnState {
+    index: HnswIndex,
+    path: Option<PathBuf>,
+    unsaved: usize,
+}
+
+impl AnnState {
+    fn touched(&mut self) {
+        self.unsaved += 1;
+        if self.unsaved >= ANN_SAVE_EVERY {
+            self.save();
+        }
+    }
+
+    fn save(&mut self) {
+        if let Some(ref path) = self.path {
+            match self.index.save(path) {
+                Ok(()) => self.unsaved = 0,
+                Err(e) => warn!(error = %e, "Failed to persist vector index"),
+            }
+        }
+    }
+}
+
+impl Drop for AnnState {
+    fn drop(&mut self) {
+        if self.unsaved > 0 {
+            self.save();
+        }
+    }
 }
 
 impl SemanticStore {
@@ -39,6 +89,8 @@ impl SemanticStore {
             conn,
             #[cfg(feature = "http-memory")]
             http_client: None,
+            ann: None,
+            keyword_weight: DEFAULT_KEYWORD_WEIGHT,
         }
     }
 
@@ -50,6 +102,112 @@ impl SemanticStore {
         Self {
             conn,
             http_client: Some(client),
+            ann: None,
+            keyword_weight: DEFAULT_KEYWORD_WEIGHT,
+        }
+    }
+
+    /// Enable the ANN vector index.
+    ///
+    /// With a `path`, a previously persisted index is loaded from it (if
+    /// present and readable) and brought up to date with the `memories`
+    /// table; otherwise the index is built from the table.
+    pub fn with_vector_index(mut self, path: Option<PathBuf>) -> OpenFangResult<Self> {
+        let loaded = path.as_deref().and_then(HnswIndex::load);
+        let from_disk = loaded.is_some();
+        self.ann = Some(Arc::new(Mutex::new(AnnState {
+            index: loaded.unwrap_or_default(),
+            path,
+            unsaved: 0,
+        })));
+        let changed = self.sync_index()?;
+        if from_disk {
+            debug!(changed, "Loaded persisted vector index");
+        }
+        Ok(self)
+    }
+
+    /// Set the weight of the BM25 keyword score in hybrid recall (0.0-1.0).
+    pub fn with_keyword_weight(mut self, weight: f32) -> Self {
+        self.keyword_weight = weight.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Bring the ANN index in line with the `memories` table: add live
+    /// embedded memories it lacks and drop entries that were deleted.
+    ///
+    /// Returns the number of entries added or removed. Used at startup and
+    /// after bulk writes that bypass this store (consolidation, import).
+    pub fn sync_index(&self) -> OpenFangResult<usize> {
+        let Some(ref ann) = self.ann else {
+            return Ok(0);
+        };
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?;
+        let mut state = ann
+            .lock()
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?;
+
+        let mut stmt = conn
+            .prepare("SELECT id FROM memories WHERE deleted = 0 AND embedding IS NOT NULL")
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        let live: std::collections::HashSet<String> = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?
+            .collect::<Result<_, _>>()
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+
+        let stale: Vec<String> = state
+            .index
+            .ids()
+            .filter(|id| !live.contains(*id))
+            .map(|id| id.to_string())
+            .collect();
+        let mut changed = stale.len();
+        for id in &stale {
+            state.index.remove(id);
+        }
+
+        let mut get = conn
+            .prepare("SELECT embedding FROM memories WHERE id = ?1")
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        let missing: Vec<&String> = live.iter().filter(|id| !state.index.contains(id)).collect();
+        for id in missing {
+            let bytes: Vec<u8> = get
+                .query_row([id], |row| row.get(0))
+                .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+            if state.index.insert(id, &embedding_from_bytes(&bytes)) {
+                changed += 1;
+            }
+        }
+
+        if changed > 0 {
+            state.unsaved += changed;
+            state.save();
+        }
+        Ok(changed)
+    }
+
+    /// Discard the ANN index and rebuild it from the `memories` table.
+    pub fn rebuild_index(&self) -> OpenFangResult<usize> {
+        let Some(ref ann) = self.ann else {
+            return Ok(0);
+        };
+        ann.lock()
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?
+            .index = HnswIndex::new();
+        self.sync_index()
+    }
+
+    /// Apply a change to the ANN index, if enabled.
+    fn update_index(&self, f: impl FnOnce(&mut HnswIndex)) {
+        if let Some(ref ann) = self.ann {
+            if let Ok(mut state) = ann.lock() {
+                f(&mut state.index);
+                state.touched();
+            }
         }
     }
 
@@ -125,6 +283,11 @@ impl SemanticStore {
             ],
         )
         .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        if let Some(embedding) = embedding {
+            self.update_index(|index| {
+                index.insert(&id.0.to_string(), embedding);
+            });
+        }
         Ok(id)
     }
 
@@ -214,10 +377,7 @@ impl SemanticStore {
             limit
         };
 
-        let mut sql = String::from(
-            "SELECT id, agent_id, content, source, scope, confidence, metadata, created_at, accessed_at, access_count, embedding
-             FROM memories WHERE deleted = 0",
-        );
+        let mut sql = format!("SELECT {MEMORY_COLUMNS} FROM memories WHERE deleted = 0");
         let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
         let mut param_idx = 1;
 
@@ -230,140 +390,72 @@ impl SemanticStore {
 
         // Apply filters
         if let Some(ref f) = filter {
-            if let Some(agent_id) = f.agent_id {
-                sql.push_str(&format!(" AND agent_id = ?{param_idx}"));
-                params.push(Box::new(agent_id.0.to_string()));
-                param_idx += 1;
-            }
-            if let Some(ref scope) = f.scope {
-                sql.push_str(&format!(" AND scope = ?{param_idx}"));
-                params.push(Box::new(scope.clone()));
-                param_idx += 1;
-            }
-            if let Some(min_conf) = f.min_confidence {
-                sql.push_str(&format!(" AND confidence >= ?{param_idx}"));
-                params.push(Box::new(min_conf as f64));
-                param_idx += 1;
-            }
-            if let Some(ref source) = f.source {
-                let source_str = serde_json::to_string(source)
-                    .map_err(|e| OpenFangError::Serialization(e.to_string()))?;
-                sql.push_str(&format!(" AND source = ?{param_idx}"));
-                params.push(Box::new(source_str));
-                let _ = param_idx;
-            }
+            push_filter_sql(f, &mut sql, &mut params, &mut param_idx)?;
         }
 
-        sql.push_str(" ORDER BY accessed_at DESC, access_count DESC");
-        sql.push_str(&format!(" LIMIT {fetch_limit}"));
-
-        let mut stmt = conn
-            .prepare(&sql)
-            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
-
-        let param_refs: Vec<&dyn rusqlite::types::ToSql> =
-            params.iter().map(|p| p.as_ref()).collect();
-        let rows = stmt
-            .query_map(param_refs.as_slice(), |row| {
-                let id_str: String = row.get(0)?;
-                let agent_str: String = row.get(1)?;
-                let content: String = row.get(2)?;
-                let source_str: String = row.get(3)?;
-                let scope: String = row.get(4)?;
-                let confidence: f64 = row.get(5)?;
-                let meta_str: String = row.get(6)?;
-                let created_str: String = row.get(7)?;
-                let accessed_str: String = row.get(8)?;
-                let access_count: i64 = row.get(9)?;
-                let embedding_bytes: Option<Vec<u8>> = row.get(10)?;
-                Ok((
-                    id_str,
-                    agent_str,
-                    content,
-                    source_str,
-                    scope,
-                    confidence,
-                    meta_str,
-                    created_str,
-                    accessed_str,
-                    access_count,
-                    embedding_bytes,
-                ))
-            })
-            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        // Most recently used memories are always candidates, so results are
+        // never worse than a plain recency window.
+        let recency_sql =
+            format!("{sql} ORDER BY accessed_at DESC, access_count DESC LIMIT {fetch_limit}");
+        let mut fragments = query_fragments(&conn, &recency_sql, &params)?;
 
-        let mut fragments = Vec::new();
-        for row_result in rows {
-            let (
-                id_str,
-                agent_str,
-                content,
-                source_str,
-                scope,
-                confidence,
-                meta_str,
-                created_str,
-                accessed_str,
-                access_count,
-                embedding_bytes,
-            ) = row_result.map_err(|e| OpenFangError::Memory(e.to_string()))?;
-
-            let id = uuid::Uuid::parse_str(&id_str)
-                .map(MemoryId)
-                .map_err(|e| OpenFangError::Memory(e.to_string()))?;
-            let agent_id = uuid::Uuid::parse_str(&agent_str)
-                .map(openfang_types::agent::AgentId)
-                .map_err(|e| OpenFangError::Memory(e.to_string()))?;
-            let source: MemorySource =
-                serde_json::from_str(&source_str).unwrap_or(MemorySource::System);
-            let metadata: HashMap<String, serde_json::Value> =
-                serde_json::from_str(&meta_str).unwrap_or_default();
-            let created_at = chrono::DateTime::parse_from_rfc3339(&created_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-            let accessed_at = chrono::DateTime::parse_from_rfc3339(&accessed_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-
-            let embedding = embedding_bytes.as_deref().map(embedding_from_bytes);
-
-            fragments.push(MemoryFragment {
-                id,
-                agent_id,
-                content,
-                embedding,
-                metadata,
-                source,
-                confidence: confidence as f32,
-                created_at,
-                accessed_at,
-                access_count: access_count as u64,
-                scope,
-            });
-        }
-
-        // If we have a query embedding, re-rank by cosine similarity
+        // If we have a query embedding, add ANN and keyword candidates, then
+        // re-rank everything with the hybrid score.
         if let Some(qe) = query_embedding {
-            fragments.sort_by(|a, b| {
-                let sim_a = a
-                    .embedding
-                    .as_deref()
-                    .map(|e| cosine_similarity(qe, e))
-                    .unwrap_or(-1.0);
-                let sim_b = b
-                    .embedding
-                    .as_deref()
-                    .map(|e| cosine_similarity(qe, e))
-                    .unwrap_or(-1.0);
-                sim_b
-                    .partial_cmp(&sim_a)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
-            fragments.truncate(limit);
+            let agent_filter = filter.as_ref().and_then(|f| f.agent_id);
+            let keyword_scores = if self.keyword_weight > 0.0 {
+                keyword_candidates(&conn, query, agent_filter, fetch_limit)
+            } else {
+                HashMap::new()
+            };
+            let mut extra: Vec<String> = keyword_scores.keys().cloned().collect();
+            if let Some(ref ann) = self.ann {
+                let state = ann
+                    .lock()
+                    .map_err(|e| OpenFangError::Internal(e.to_string()))?;
+                // The index spans all agents; over-fetch so filtering by
+                // agent still leaves enough candidates.
+                let k = fetch_limit * if agent_filter.is_some() { 4 } else { 1 };
+                extra.extend(state.index.search(qe, k, k).into_iter().map(|(id, _)| id));
+            }
+            let seen: std::collections::HashSet<String> =
+                fragments.iter().map(|f| f.id.0.to_string()).collect();
+            extra.retain(|id| !seen.contains(id));
+            extra.sort();
+            extra.dedup();
+            if !extra.is_empty() {
+                let ids_json = serde_json::to_string(&extra)
+                    .map_err(|e| OpenFangError::Serialization(e.to_string()))?;
+                let candidate_sql =
+                    format!("{sql} AND id IN (SELECT value FROM json_each(?{param_idx}))");
+                params.push(Box::new(ids_json));
+                fragments.extend(query_fragments(&conn, &candidate_sql, &params)?);
+            }
+
+            let w = self.keyword_weight;
+            let mut scored: Vec<(f32, MemoryFragment)> = fragments
+                .into_iter()
+                .map(|frag| {
+                    let vector = frag
+                        .embedding
+                        .as_deref()
+                        .filter(|e| e.len() == qe.len())
+                        .map(|e| cosine_similarity(qe, e))
+                        .unwrap_or(-1.0);
+                    let keyword = keyword_scores
+                        .get(&frag.id.0.to_string())
+                        .copied()
+                        .unwrap_or(0.0);
+                    ((1.0 - w) * vector + w * keyword, frag)
+                })
+                .collect();
+            scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+            let candidates = scored.len();
+            fragments = scored.into_iter().take(limit).map(|(_, f)| f).collect();
             debug!(
-                "Vector recall: {} results from {} candidates",
+                "Hybrid recall: {} results from {} candidates",
                 fragments.len(),
-                fetch_limit
+                candidates
             );
         }
 
@@ -397,6 +489,9 @@ impl SemanticStore {
             rusqlite::params![id.0.to_string()],
         )
         .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        self.update_index(|index| {
+            index.remove(&id.0.to_string());
+        });
         Ok(())
     }
 
@@ -407,11 +502,17 @@ impl SemanticStore {
             .lock()
             .map_err(|e| OpenFangError::Internal(e.to_string()))?;
         let bytes = embedding_to_bytes(embedding);
-        conn.execute(
-            "UPDATE memories SET embedding = ?1 WHERE id = ?2",
-            rusqlite::params![bytes, id.0.to_string()],
-        )
-        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        let updated = conn
+            .execute(
+                "UPDATE memories SET embedding = ?1 WHERE id = ?2",
+                rusqlite::params![bytes, id.0.to_string()],
+            )
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        if updated > 0 {
+            self.update_index(|index| {
+                index.insert(&id.0.to_string(), embedding);
+            });
+        }
         Ok(())
     }
 
@@ -428,14 +529,26 @@ impl SemanticStore {
         filter: &Option<MemoryFilter>,
     ) -> OpenFangResult<Vec<MemoryFragment>> {
         let category = filter.as_ref().and_then(|f| f.scope.as_deref());
+        let filters = filter
+            .as_ref()
+            .map(|f| SearchFilters {
+                agent_id: f.agent_id.map(|id| id.0.to_string()),
+                created_after: f.after.map(|t| t.timestamp_millis()),
+                created_before: f.before.map(|t| t.timestamp_millis()),
+                metadata: f.all_metadata_predicates(),
+            })
+            .unwrap_or_default();
 
         let results = client
-            .search(query, limit, category)
+            .search(query, limit, category, &filters)
             .map_err(|e| OpenFangError::Memory(format!("HTTP search failed: {e}")))?;
 
+        // Re-check time bounds and metadata locally in case the memory-api
+        // deployment ignores them. Results without metadata can't satisfy a
+        // metadata predicate and are dropped.
         let fragments: Vec<MemoryFragment> = results
             .into_iter()
-            .map(|r| {
+            .filter_map(|r| {
                 let created_at = r
                     .created_at
                     .map(|ms| {
@@ -443,19 +556,26 @@ impl SemanticStore {
                     })
                     .unwrap_or_else(Utc::now);
 
-                MemoryFragment {
+                let metadata = r.metadata.unwrap_or_default();
+                if let Some(ref f) = filter {
+                    if !f.matches_time_and_metadata(created_at, &metadata) {
+                        return None;
+                    }
+                }
+
+                Some(MemoryFragment {
                     id: MemoryId::new(),
                     agent_id: filter.as_ref().and_then(|f| f.agent_id).unwrap_or_default(),
                     content: r.content,
                     embedding: None,
-                    metadata: HashMap::new(),
+                    metadata,
                     source: MemorySource::System,
                     confidence: r.score as f32,
                     created_at,
                     accessed_at: Utc::now(),
                     access_count: 0,
                     scope: r.category.unwrap_or_else(|| "general".to_string()),
-                }
+                })
             })
             .collect();
 
@@ -467,6 +587,295 @@ impl SemanticStore {
     }
 }
 
+/// Columns selected for a `MemoryFragment`, in the order `query_fragments` reads them.
+const MEMORY_COLUMNS: &str = "id, agent_id, content, source, scope, confidence, metadata, created_at, accessed_at, access_count, embedding";
+
+/// Append `MemoryFilter` clauses to a query over `memories`.
+fn push_filter_sql(
+    f: &MemoryFilter,
+    sql: &mut String,
+    params: &mut Vec<Box<dyn rusqlite::types::ToSql>>,
+    param_idx: &mut usize,
+) -> OpenFangResult<()> {
+    if let Some(agent_id) = f.agent_id {
+        sql.push_str(&format!(" AND agent_id = ?{param_idx}"));
+        params.push(Box::new(agent_id.0.to_string()));
+        *param_idx += 1;
+    }
+    if let Some(ref scope) = f.scope {
+        sql.push_str(&format!(" AND scope = ?{param_idx}"));
+        params.push(Box::new(scope.clone()));
+        *param_idx += 1;
+    }
+    if let Some(min_conf) = f.min_confidence {
+        sql.push_str(&format!(" AND confidence >= ?{param_idx}"));
+        params.push(Box::new(min_conf as f64));
+        *param_idx += 1;
+    }
+    if let Some(ref source) = f.source {
+        let source_str = serde_json::to_string(source)
+            .map_err(|e| OpenFangError::Serialization(e.to_string()))?;
+        sql.push_str(&format!(" AND source = ?{param_idx}"));
+        params.push(Box::new(source_str));
+        *param_idx += 1;
+    }
+    // Timestamps are stored as RFC 3339 in UTC, which sorts
+    // lexicographically, so the comparison can use the index.
+    if let Some(after) = f.after {
+        sql.push_str(&format!(" AND created_at > ?{param_idx}"));
+        params.push(Box::new(after.to_rfc3339()));
+        *param_idx += 1;
+    }
+    if let Some(before) = f.before {
+        sql.push_str(&format!(" AND created_at < ?{param_idx}"));
+        params.push(Box::new(before.to_rfc3339()));
+        *param_idx += 1;
+    }
+    for predicate in f.all_metadata_predicates() {
+        push_metadata_predicate(&predicate, sql, params, param_idx);
+    }
+    Ok(())
+}
+
+/// Run a `SELECT {MEMORY_COLUMNS} ...` query and decode the rows.
+fn query_fragments(
+    conn: &Connection,
+    sql: &str,
+    params: &[Box<dyn rusqlite::types::ToSql>],
+) -> OpenFangResult<Vec<MemoryFragment>> {
+    let mut stmt = conn
+        .prepare(sql)
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+
+    let param_refs: Vec<&dyn rusqlite::types::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    let rows = stmt
+        .query_map(param_refs.as_slice(), |row| {
+            let id_str: String = row.get(0)?;
+            let agent_str: String = row.get(1)?;
+            let content: String = row.get(2)?;
+            let source_str: String = row.get(3)?;
+            let scope: String = row.get(4)?;
+            let confidence: f64 = row.get(5)?;
+            let meta_str: String = row.get(6)?;
+            let created_str: String = row.get(7)?;
+            let accessed_str: String = row.get(8)?;
+            let access_count: i64 = row.get(9)?;
+            let embedding_bytes: Option<Vec<u8>> = row.get(10)?;
+            Ok((
+                id_str,
+                agent_str,
+                content,
+                source_str,
+                scope,
+                confidence,
+                meta_str,
+                created_str,
+                accessed_str,
+                access_count,
+                embedding_bytes,
+            ))
+        })
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+
+    let mut fragments = Vec::new();
+    for row_result in rows {
+        let (
+            id_str,
+            agent_str,
+            content,
+            source_str,
+            scope,
+            confidence,
+            meta_str,
+            created_str,
+            accessed_str,
+            access_count,
+            embedding_bytes,
+        ) = row_result.map_err(|e| OpenFangError::Memory(e.to_string()))?;
+
+        let id = uuid::Uuid::parse_str(&id_str)
+            .map(MemoryId)
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        let agent_id = uuid::Uuid::parse_str(&agent_str)
+            .map(openfang_types::agent::AgentId)
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        let source: MemorySource =
+            serde_json::from_str(&source_str).unwrap_or(MemorySource::System);
+        let metadata: HashMap<String, serde_json::Value> =
+            serde_json::from_str(&meta_str).unwrap_or_default();
+        let created_at = chrono::DateTime::parse_from_rfc3339(&created_str)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now());
+        let accessed_at = chrono::DateTime::parse_from_rfc3339(&accessed_str)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now());
+
+        let embedding = embedding_bytes.as_deref().map(embedding_from_bytes);
+
+        fragments.push(MemoryFragment {
+            id,
+            agent_id,
+            content,
+            embedding,
+            metadata,
+            source,
+            confidence: confidence as f32,
+            created_at,
+            accessed_at,
+            access_count: access_count as u64,
+            scope,
+        });
+    }
+    Ok(fragments)
+}
+
+/// Split free text into quoted FTS5 terms, dropping punctuation so user
+/// input can never be parsed as FTS5 query syntax.
+pub(crate) fn fts_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| format!("\"{w}\""))
+        .collect()
+}
+
+/// BM25 keyword matches for a query, as memory ID -> score in `(0, 1]`
+/// (best match = 1). Empty when the query has no searchable words or the
+/// FTS index is unavailable.
+fn keyword_candidates(
+    conn: &Connection,
+    query: &str,
+    agent_id: Option<AgentId>,
+    limit: usize,
+) -> HashMap<String, f32> {
+    let terms = fts_terms(query);
+    if terms.is_empty() {
+        return HashMap::new();
+    }
+    let fts_query = terms.join(" OR ");
+    let agent = agent_id.map(|a| a.0.to_string());
+    let result = conn
+        .prepare(
+            "SELECT m.id, bm25(memories_fts) FROM memories_fts
+             JOIN memories m ON m.rowid = memories_fts.rowid
+             WHERE memories_fts MATCH ?1 AND m.deleted = 0
+               AND (?2 IS NULL OR m.agent_id = ?2)
+             ORDER BY bm25(memories_fts) LIMIT ?3",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(rusqlite::params![fts_query, agent, limit as i64], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()
+        });
+    let rows = match result {
+        Ok(rows) => rows,
+        Err(e) => {
+            debug!(error = %e, "Keyword search unavailable");
+            return HashMap::new();
+        }
+    };
+    // bm25() is negative, more negative = better match.
+    let best = rows.iter().map(|(_, s)| *s).fold(0.0f64, f64::min);
+    if best >= 0.0 {
+        return rows.into_iter().map(|(id, _)| (id, 1.0)).collect();
+    }
+    rows.into_iter()
+        .map(|(id, s)| (id, (s / best) as f32))
+        .collect()
+}
+
+/// Append a `json_extract`-based clause for one metadata predicate.
+///
+/// The JSON path is bound as a parameter so arbitrary keys are safe.
+fn push_metadata_predicate(
+    predicate: &MetadataPredicate,
+    sql: &mut String,
+    params: &mut Vec<Box<dyn rusqlite::types::ToSql>>,
+    idx: &mut usize,
+) {
+    let path = format!("$.\"{}\"", predicate.key().replace('"', "\\\""));
+    let path_idx = *idx;
+    params.push(Box::new(path));
+    *idx += 1;
+
+    match predicate {
+        MetadataPredicate::Exists { exists, .. } => {
+            let op = if *exists { "IS NOT NULL" } else { "IS NULL" };
+            sql.push_str(&format!(" AND json_type(metadata, ?{path_idx}) {op}"));
+        }
+        MetadataPredicate::Equals { value, .. } => {
+            sql.push_str(" AND ");
+            push_json_equals(value, path_idx, sql, params, idx);
+        }
+        MetadataPredicate::In { values, .. } => {
+            if values.is_empty() {
+                // Nothing can match; still reference the bound path.
+                sql.push_str(&format!(" AND ?{path_idx} IS NULL"));
+                return;
+            }
+            sql.push_str(" AND (");
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    sql.push_str(" OR ");
+                }
+                push_json_equals(value, path_idx, sql, params, idx);
+            }
+            sql.push(')');
+        }
+    }
+}
+
+/// Append `metadata[path] == value`, binding `value` the way `json_extract`
+/// returns it (text, integer, real, 0/1 for booleans, minified JSON for
+/// arrays and objects).
+fn push_json_equals(
+    value: &serde_json::Value,
+    path_idx: usize,
+    sql: &mut String,
+    params: &mut Vec<Box<dyn rusqlite::types::ToSql>>,
+    idx: &mut usize,
+) {
+    use serde_json::Value;
+    match value {
+        Value::Null => {
+            sql.push_str(&format!("json_type(metadata, ?{path_idx}) = 'null'"));
+            return;
+        }
+        Value::Bool(b) => {
+            sql.push_str(&format!(
+                "(json_type(metadata, ?{path_idx}) IN ('true', 'false') \
+                 AND json_extract(metadata, ?{path_idx}) = ?{idx})"
+            ));
+            params.push(Box::new(*b as i64));
+        }
+        Value::Number(n) => {
+            sql.push_str(&format!(
+                "(json_type(metadata, ?{path_idx}) IN ('integer', 'real') \
+                 AND json_extract(metadata, ?{path_idx}) = ?{idx})"
+            ));
+            match n.as_i64() {
+                Some(i) => params.push(Box::new(i)),
+                None => params.push(Box::new(n.as_f64().unwrap_or(f64::NAN))),
+            }
+        }
+        Value::String(text) => {
+            sql.push_str(&format!(
+                "(json_type(metadata, ?{path_idx}) = 'text' \
+                 AND json_extract(metadata, ?{path_idx}) = ?{idx})"
+            ));
+            params.push(Box::new(text.clone()));
+        }
+        Value::Array(_) | Value::Object(_) => {
+            sql.push_str(&format!(
+                "json_extract(metadata, ?{path_idx}) = json(?{idx})"
+            ));
+            params.push(Box::new(value.to_string()));
+        }
+    }
+    *idx += 1;
+}
+
 /// Compute cosine similarity between two vectors.
 fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() || a.is_empty() {
@@ -516,6 +925,171 @@ mod tests {
         SemanticStore::new(Arc::new(Mutex::new(conn)))
     }
 
+    fn setup_indexed() -> SemanticStore {
+        setup().with_vector_index(None).unwrap()
+    }
+
+    /// Unit vector along a pseudo-random direction derived from `seed`.
+    fn direction(seed: u64, dim: usize) -> Vec<f32> {
+        let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+        (0..dim)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state % 1000) as f32 / 1000.0 - 0.5
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ann_recall_finds_memories_outside_recency_window() {
+        let target = direction(7, 16);
+        for (store, expect_found) in [(setup(), false), (setup_indexed(), true)] {
+            let agent_id = AgentId::new();
+            let old = store
+                .remember_with_embedding(
+                    agent_id,
+                    "old but relevant",
+                    MemorySource::Conversation,
+                    "episodic",
+                    HashMap::new(),
+                    Some(&target),
+                )
+                .unwrap();
+            store
+                .conn
+                .lock()
+                .unwrap()
+                .execute(
+                    "UPDATE memories SET accessed_at = '2000-01-01T00:00:00+00:00' WHERE id = ?1",
+                    rusqlite::params![old.0.to_string()],
+                )
+                .unwrap();
+            for i in 0..150u64 {
+                store
+                    .remember_with_embedding(
+                        agent_id,
+                        &format!("filler {i}"),
+                        MemorySource::Conversation,
+                        "episodic",
+                        HashMap::new(),
+                        Some(&direction(1000 + i, 16)),
+                    )
+                    .unwrap();
+            }
+            let results = store
+                .recall_with_embedding("", 1, Some(MemoryFilter::agent(agent_id)), Some(&target))
+                .unwrap();
+            assert_eq!(results[0].id == old, expect_found);
+        }
+    }
+
+    #[test]
+    fn test_hybrid_recall_uses_keyword_score() {
+        let query = [0.0, 0.6, 0.8];
+        let top_for = |keyword_weight: f32| {
+            let store = setup_indexed().with_keyword_weight(keyword_weight);
+            let agent_id = AgentId::new();
+            for (content, embedding) in [
+                ("Weekly sync moved to Thursday", [0.6, 0.8, 0.0]),
+                ("The deploy password rotates monthly", [0.8, 0.6, 0.0]),
+            ] {
+                store
+                    .remember_with_embedding(
+                        agent_id,
+                        content,
+                        MemorySource::Conversation,
+                        "episodic",
+                        HashMap::new(),
+                        Some(&embedding),
+                    )
+                    .unwrap();
+            }
+            store
+                .recall_with_embedding("deploy password", 2, None, Some(&query))
+                .unwrap()
+                .remove(0)
+                .content
+        };
+        // Vector similarity alone prefers the sync note; the keyword match
+        // lifts the password note above it in hybrid mode.
+        assert!(top_for(0.0).contains("Weekly"));
+        assert!(top_for(DEFAULT_KEYWORD_WEIGHT).contains("password"));
+    }
+
+    #[test]
+    fn test_vector_index_stays_in_sync_and_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("mem.db");
+        let index_path = dir.path().join("mem.db.hnsw");
+        let open = || {
+            let conn = Connection::open(&db).unwrap();
+            run_migrations(&conn).unwrap();
+            SemanticStore::new(Arc::new(Mutex::new(conn)))
+                .with_vector_index(Some(index_path.clone()))
+                .unwrap()
+        };
+        let ann_len = |s: &SemanticStore| s.ann.as_ref().unwrap().lock().unwrap().index.len();
+
+        let store = open();
+        let agent_id = AgentId::new();
+        let a = store
+            .remember_with_embedding(
+                agent_id,
+                "a",
+                MemorySource::System,
+                "episodic",
+                HashMap::new(),
+                Some(&[1.0, 0.0]),
+            )
+            .unwrap();
+        let b = store
+            .remember_with_embedding(
+                agent_id,
+                "b",
+                MemorySource::System,
+                "episodic",
+                HashMap::new(),
+                Some(&[0.0, 1.0]),
+            )
+            .unwrap();
+        let c = store
+            .remember(
+                agent_id,
+                "c",
+                MemorySource::System,
+                "episodic",
+                HashMap::new(),
+            )
+            .unwrap();
+        assert_eq!(ann_len(&store), 2);
+
+        store.forget(a).unwrap();
+        store.update_embedding(c, &[1.0, 0.0]).unwrap();
+        assert_eq!(ann_len(&store), 2);
+        let top = store
+            .recall_with_embedding("", 1, None, Some(&[1.0, 0.0]))
+            .unwrap();
+        assert_eq!(top[0].id, c);
+        drop(store);
+        assert!(index_path.exists());
+
+        // Reopen from the persisted file, with a write that bypassed the store.
+        {
+            let conn = Connection::open(&db).unwrap();
+            conn.execute(
+                "UPDATE memories SET deleted = 1 WHERE id = ?1",
+                rusqlite::params![b.0.to_string()],
+            )
+            .unwrap();
+        }
+        let store = open();
+        assert_eq!(ann_len(&store), 1);
+        assert_eq!(store.rebuild_index().unwrap(), 1);
+        assert_eq!(ann_len(&store), 1);
+    }
+
     #[test]
     fn test_remember_and_recall() {
         let store = setup();
@@ -562,6 +1136,136 @@ mod tests {
         assert_eq!(results[0].content, "Memory A");
     }
 
+    #[test]
+    fn test_recall_time_range() {
+        let store = setup();
+        let agent_id = AgentId::new();
+        let old = store
+            .remember(
+                agent_id,
+                "Memory from last month",
+                MemorySource::Conversation,
+                "episodic",
+                HashMap::new(),
+            )
+            .unwrap();
+        store
+            .remember(
+                agent_id,
+                "Memory from today",
+                MemorySource::Conversation,
+                "episodic",
+                HashMap::new(),
+            )
+            .unwrap();
+        let month_ago = (Utc::now() - chrono::Duration::days(30)).to_rfc3339();
+        store
+            .conn
+            .lock()
+            .unwrap()
+            .execute(
+                "UPDATE memories SET created_at = ?1 WHERE id = ?2",
+                rusqlite::params![month_ago, old.0.to_string()],
+            )
+            .unwrap();
+
+        let week_ago = Utc::now() - chrono::Duration::days(7);
+        let recent = store
+            .recall(
+                "Memory",
+                10,
+                Some(MemoryFilter {
+                    after: Some(week_ago),
+                    ..MemoryFilter::agent(agent_id)
+                }),
+            )
+            .unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].content, "Memory from today");
+
+        let older = store
+            .recall(
+                "Memory",
+                10,
+                Some(MemoryFilter {
+                    before: Some(week_ago),
+                    ..MemoryFilter::agent(agent_id)
+                }),
+            )
+            .unwrap();
+        assert_eq!(older.len(), 1);
+        assert_eq!(older[0].content, "Memory from last month");
+    }
+
+    #[test]
+    fn test_recall_metadata_predicates() {
+        let store = setup();
+        let agent_id = AgentId::new();
+        let entries = [
+            ("A", serde_json::json!({"topic": "rust", "priority": 1})),
+            (
+                "B",
+                serde_json::json!({"topic": "go", "priority": 2, "pinned": true}),
+            ),
+            ("C", serde_json::json!({"topic": "rust", "priority": "1"})),
+            ("D", serde_json::json!({"weird.key": [1, 2]})),
+        ];
+        for (content, meta) in entries {
+            let meta: HashMap<String, serde_json::Value> = serde_json::from_value(meta).unwrap();
+            store
+                .remember(agent_id, content, MemorySource::System, "episodic", meta)
+                .unwrap();
+        }
+        let recall = |filter: MemoryFilter| {
+            let mut out: Vec<String> = store
+                .recall("", 10, Some(filter))
+                .unwrap()
+                .into_iter()
+                .map(|m| m.content)
+                .collect();
+            out.sort();
+            out
+        };
+
+        let mut eq = MemoryFilter::agent(agent_id);
+        eq.metadata
+            .insert("topic".to_string(), serde_json::json!("rust"));
+        assert_eq!(recall(eq.clone()), vec!["A", "C"]);
+
+        // Numbers don't match their string spelling.
+        eq.metadata
+            .insert("priority".to_string(), serde_json::json!(1));
+        assert_eq!(recall(eq), vec!["A"]);
+
+        let mut filter = MemoryFilter::agent(agent_id);
+        filter.metadata_predicates = vec![MetadataPredicate::In {
+            key: "priority".to_string(),
+            values: vec![serde_json::json!(2), serde_json::json!("1")],
+        }];
+        assert_eq!(recall(filter), vec!["B", "C"]);
+
+        let mut filter = MemoryFilter::agent(agent_id);
+        filter.metadata_predicates = vec![MetadataPredicate::Exists {
+            key: "pinned".to_string(),
+            exists: false,
+        }];
+        assert_eq!(recall(filter), vec!["A", "C", "D"]);
+
+        let mut filter = MemoryFilter::agent(agent_id);
+        filter.metadata_predicates = vec![MetadataPredicate::Equals {
+            key: "weird.key".to_string(),
+            value: serde_json::json!([1, 2]),
+        }];
+        assert_eq!(recall(filter), vec!["D"]);
+
+        let mut filter = MemoryFilter::agent(agent_id);
+        filter.metadata_predicates = vec![MetadataPredicate::In {
+            key: "topic".to_string(),
+            values: vec![],
+        }];
+        assert!(recall(filter).is_empty());
+    }
+
     #[test]
     fn test_forget() {
         let store = setup();
@@ -1,10 +1,12 @@
 //! Session management — load/save conversation history.
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use openfang_types::agent::{AgentId, SessionId};
 use openfang_types::error::{OpenFangError, OpenFangResult};
 use openfang_types::message::{ContentBlock, Message, MessageContent, Role};
 use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
@@ -83,7 +85,10 @@ impl SessionStore {
         let messages_blob = rmp_serde::to_vec_named(&session.messages)
             .map_err(|e| OpenFangError::Serialization(e.to_string()))?;
         let now = Utc::now().to_rfc3339();
-        conn.execute(
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        tx.execute(
             "INSERT INTO sessions (id, agent_id, messages, context_window_tokens, label, created_at, updated_at)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
              ON CONFLICT(id) DO UPDATE SET messages = ?3, context_window_tokens = ?4, label = ?5, updated_at = ?6",
@@ -97,6 +102,16 @@ impl SessionStore {
             ],
         )
         .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        index_session_messages(
+            &tx,
+            &session.id.0.to_string(),
+            &session.agent_id.0.to_string(),
+            &session.messages,
+            &now,
+        )
+        .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        tx.commit()
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
         Ok(())
     }
 
@@ -471,6 +486,19 @@ impl SessionStore {
 
         canonical.updated_at = Utc::now().to_rfc3339();
         self.save_canonical(&canonical)?;
+        {
+            let conn = self
+                .conn
+                .lock()
+                .map_err(|e| OpenFangError::Internal(e.to_string()))?;
+            append_canonical_index(
+                &conn,
+                &agent_id.0.to_string(),
+                new_messages,
+                &canonical.updated_at,
+            )
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        }
         Ok(canonical)
     }
 
@@ -515,6 +543,359 @@ impl SessionStore {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Transcript search
+// ---------------------------------------------------------------------------
+
+/// Default number of hits returned by [`SessionStore::search`].
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// Maximum number of hits a single search may return.
+pub const MAX_SEARCH_LIMIT: usize = 200;
+
+/// A full-text query over session transcripts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionSearchQuery {
+    /// Words to search for. All words must appear in a message.
+    pub query: String,
+    /// Only search this agent's transcripts.
+    pub agent_id: Option<AgentId>,
+    /// Only search one session.
+    pub session_id: Option<SessionId>,
+    /// Only match messages sent with this role.
+    pub role: Option<Role>,
+    /// Only match messages recorded at or after this time.
+    pub after: Option<DateTime<Utc>>,
+    /// Only match messages recorded before this time.
+    pub before: Option<DateTime<Utc>>,
+    /// Maximum hits to return (0 = [`DEFAULT_SEARCH_LIMIT`]).
+    pub limit: usize,
+    /// Hits to skip, for paging.
+    pub offset: usize,
+    /// Marker inserted before each matched word in the snippet.
+    pub highlight_start: String,
+    /// Marker inserted after each matched word in the snippet.
+    pub highlight_end: String,
+}
+
+impl Default for SessionSearchQuery {
+    fn default() -> Self {
+        Self {
+            query: String::new(),
+            agent_id: None,
+            session_id: None,
+            role: None,
+            after: None,
+            before: None,
+            limit: 0,
+            offset: 0,
+            highlight_start: "<mark>".to_string(),
+            highlight_end: "</mark>".to_string(),
+        }
+    }
+}
+
+impl SessionSearchQuery {
+    /// Create a query for the given words with default filters.
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A message that matched a [`SessionSearchQuery`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSearchHit {
+    /// Session the message belongs to (`None` for the canonical session).
+    pub session_id: Option<SessionId>,
+    /// Agent that owns the transcript.
+    pub agent_id: AgentId,
+    /// Session label, if any.
+    pub label: Option<String>,
+    /// Position of the message in its transcript.
+    pub message_index: usize,
+    /// Who sent the message.
+    pub role: Role,
+    /// Excerpt around the match with highlight markers.
+    pub snippet: String,
+    /// When the message was first indexed (RFC 3339).
+    pub created_at: String,
+    /// Relevance (higher is better).
+    pub score: f32,
+}
+
+impl SessionStore {
+    /// Full-text search over session and canonical transcripts.
+    ///
+    /// Messages that appear in both a session and the canonical session are
+    /// reported once, preferring the session copy.
+    pub fn search(&self, query: &SessionSearchQuery) -> OpenFangResult<Vec<SessionSearchHit>> {
+        let terms = crate::semantic::fts_terms(&query.query);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let limit = match query.limit {
+            0 => DEFAULT_SEARCH_LIMIT,
+            n => n.min(MAX_SEARCH_LIMIT),
+        };
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT sm.session_id, sm.agent_id, s.label, sm.msg_index, sm.role,
+                        snippet(session_messages_fts, 0, ?7, ?8, '…', 24),
+                        sm.created_at, bm25(session_messages_fts), sm.content
+                 FROM session_messages_fts
+                 JOIN session_messages sm ON sm.id = session_messages_fts.rowid
+                 LEFT JOIN sessions s ON s.id = sm.session_id
+                 WHERE session_messages_fts MATCH ?1
+                   AND (?2 IS NULL OR sm.agent_id = ?2)
+                   AND (?3 IS NULL OR sm.session_id = ?3)
+                   AND (?4 IS NULL OR sm.role = ?4)
+                   AND (?5 IS NULL OR sm.created_at >= ?5)
+                   AND (?6 IS NULL OR sm.created_at < ?6)
+                 ORDER BY bm25(session_messages_fts), sm.source = 'session' DESC,
+                          sm.created_at DESC",
+            )
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        let mut rows = stmt
+            .query(rusqlite::params![
+                terms.join(" "),
+                query.agent_id.map(|a| a.0.to_string()),
+                query.session_id.map(|s| s.0.to_string()),
+                query.role.map(role_str),
+                query.after.map(|t| t.to_rfc3339()),
+                query.before.map(|t| t.to_rfc3339()),
+                query.highlight_start,
+                query.highlight_end,
+            ])
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?;
+
+        let mut seen = HashSet::new();
+        let mut skipped = 0;
+        let mut hits = Vec::new();
+        while let Some(row) = rows
+            .next()
+            .map_err(|e| OpenFangError::Memory(e.to_string()))?
+        {
+            let read = || -> rusqlite::Result<_> {
+                Ok((
+                    row.get::<_, Option<String>>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, String>(5)?,
+                    row.get::<_, String>(6)?,
+                    row.get::<_, f64>(7)?,
+                    row.get::<_, String>(8)?,
+                ))
+            };
+            let (session, agent, label, index, role, snippet, created_at, bm25, content) =
+                read().map_err(|e| OpenFangError::Memory(e.to_string()))?;
+            if !seen.insert((agent.clone(), role.clone(), content)) {
+                continue;
+            }
+            if skipped < query.offset {
+                skipped += 1;
+                continue;
+            }
+            let Ok(agent_id) = uuid::Uuid::parse_str(&agent).map(AgentId) else {
+                continue;
+            };
+            hits.push(SessionSearchHit {
+                session_id: session
+                    .and_then(|s| uuid::Uuid::parse_str(&s).ok())
+                    .map(SessionId),
+                agent_id,
+                label,
+                message_index: index as usize,
+                role: parse_role(&role),
+                snippet,
+                created_at,
+                // bm25() is negative, more negative = better match.
+                score: (-bm25) as f32,
+            });
+            if hits.len() >= limit {
+                break;
+            }
+        }
+        Ok(hits)
+    }
+}
+
+/// Lowercase role name, as stored in the search index.
+fn role_str(role: Role) -> &'static str {
+    match role {
+        Role::User => "user",
+        Role::Assistant => "assistant",
+        Role::System => "system",
+    }
+}
+
+fn parse_role(s: &str) -> Role {
+    match s {
+        "assistant" => Role::Assistant,
+        "system" => Role::System,
+        _ => Role::User,
+    }
+}
+
+/// Searchable text of a message, or `None` if it has none.
+fn searchable_text(msg: &Message) -> Option<String> {
+    let text = msg.content.text_content();
+    let trimmed = text.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+/// Bring a session's rows in the search index in line with its messages.
+///
+/// Rows are matched by role and text rather than position, so messages keep
+/// their original `created_at` when earlier history is compacted away. New
+/// messages are stamped with `now`; rows no longer in the session are removed.
+pub(crate) fn index_session_messages(
+    conn: &Connection,
+    session_id: &str,
+    agent_id: &str,
+    messages: &[Message],
+    now: &str,
+) -> rusqlite::Result<()> {
+    let mut existing: HashMap<(String, String), Vec<(i64, i64)>> = HashMap::new();
+    {
+        let mut stmt = conn.prepare(
+            "SELECT id, msg_index, role, content FROM session_messages
+             WHERE source = 'session' AND session_id = ?1 ORDER BY msg_index DESC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![session_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?;
+        for row in rows {
+            let (id, index, role, content) = row?;
+            existing
+                .entry((role, content))
+                .or_default()
+                .push((id, index));
+        }
+    }
+
+    for (index, msg) in messages.iter().enumerate() {
+        let Some(text) = searchable_text(msg) else {
+            continue;
+        };
+        let role = role_str(msg.role);
+        // Rows are popped lowest index first, preserving message order.
+        match existing
+            .get_mut(&(role.to_string(), text.clone()))
+            .and_then(|rows| rows.pop())
+        {
+            Some((_, old_index)) if old_index == index as i64 => {}
+            Some((id, _)) => {
+                conn.execute(
+                    "UPDATE session_messages SET msg_index = ?1 WHERE id = ?2",
+                    rusqlite::params![index as i64, id],
+                )?;
+            }
+            None => {
+                conn.execute(
+                    "INSERT INTO session_messages (session_id, agent_id, source, msg_index, role, content, created_at)
+                     VALUES (?1, ?2, 'session', ?3, ?4, ?5, ?6)",
+                    rusqlite::params![session_id, agent_id, index as i64, role, text, now],
+                )?;
+            }
+        }
+    }
+
+    for (id, _) in existing.into_values().flatten() {
+        conn.execute(
+            "DELETE FROM session_messages WHERE id = ?1",
+            rusqlite::params![id],
+        )?;
+    }
+    Ok(())
+}
+
+/// Add messages appended to an agent's canonical session to the search index.
+///
+/// Canonical rows are never pruned by compaction, so the index keeps the
+/// agent's full cross-channel history until the canonical session is deleted.
+pub(crate) fn append_canonical_index(
+    conn: &Connection,
+    agent_id: &str,
+    messages: &[Message],
+    now: &str,
+) -> rusqlite::Result<()> {
+    let mut next: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(msg_index) + 1, 0) FROM session_messages
+         WHERE source = 'canonical' AND agent_id = ?1",
+        rusqlite::params![agent_id],
+        |row| row.get(0),
+    )?;
+    for msg in messages {
+        let Some(text) = searchable_text(msg) else {
+            continue;
+        };
+        conn.execute(
+            "INSERT INTO session_messages (session_id, agent_id, source, msg_index, role, content, created_at)
+             VALUES (NULL, ?1, 'canonical', ?2, ?3, ?4, ?5)",
+            rusqlite::params![agent_id, next, role_str(msg.role), text, now],
+        )?;
+        next += 1;
+    }
+    Ok(())
+}
+
+/// Index the messages of a canonical session that are not indexed yet.
+///
+/// Used when canonical history is written wholesale (backfill, import)
+/// rather than appended.
+pub(crate) fn index_canonical_messages(
+    conn: &Connection,
+    agent_id: &str,
+    messages: &[Message],
+    now: &str,
+) -> rusqlite::Result<()> {
+    let mut existing: HashMap<(String, String), usize> = HashMap::new();
+    {
+        let mut stmt = conn.prepare(
+            "SELECT role, content FROM session_messages
+             WHERE source = 'canonical' AND agent_id = ?1",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![agent_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            *existing.entry(row?).or_default() += 1;
+        }
+    }
+    let missing: Vec<Message> = messages
+        .iter()
+        .filter(|msg| {
+            let Some(text) = searchable_text(msg) else {
+                return false;
+            };
+            match existing.get_mut(&(role_str(msg.role).to_string(), text)) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            }
+        })
+        .cloned()
+        .collect();
+    append_canonical_index(conn, agent_id, &missing, now)
+}
+
 /// A single JSONL line in the session mirror file.
 #[derive(serde::Serialize)]
 struct JsonlLine {
@@ -775,6 +1156,123 @@ mod tests {
         assert!(summary.is_none()); // Only 2 messages, no compaction
     }
 
+    #[test]
+    fn test_search_sessions_with_filters() {
+        let store = setup();
+        let agent_a = AgentId::new();
+        let agent_b = AgentId::new();
+        let mut session = store.create_session(agent_a).unwrap();
+        session
+            .messages
+            .push(Message::user("My router model is the Netgear R7000"));
+        session
+            .messages
+            .push(Message::assistant("Thanks, noted the Netgear router."));
+        store.save_session(&session).unwrap();
+        let mut other = store.create_session(agent_b).unwrap();
+        other.messages.push(Message::user("Netgear support hours?"));
+        store.save_session(&other).unwrap();
+
+        let hits = store.search(&SessionSearchQuery::new("netgear")).unwrap();
+        assert_eq!(hits.len(), 3);
+
+        let hits = store
+            .search(&SessionSearchQuery {
+                agent_id: Some(agent_a),
+                role: Some(Role::User),
+                ..SessionSearchQuery::new("netgear router")
+            })
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].session_id, Some(session.id));
+        assert_eq!(hits[0].message_index, 0);
+        assert!(hits[0].snippet.contains("<mark>Netgear</mark>"));
+
+        let hits = store
+            .search(&SessionSearchQuery {
+                after: Some(Utc::now() + chrono::Duration::hours(1)),
+                ..SessionSearchQuery::new("netgear")
+            })
+            .unwrap();
+        assert!(hits.is_empty());
+
+        // Punctuation is not treated as FTS5 syntax.
+        assert!(store
+            .search(&SessionSearchQuery::new("\"R7000\" OR (*"))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_search_index_follows_session_changes() {
+        let store = setup();
+        let agent_id = AgentId::new();
+        let mut session = store.create_session(agent_id).unwrap();
+        session
+            .messages
+            .push(Message::user("Order 1234 arrived broken"));
+        session
+            .messages
+            .push(Message::assistant("Sorry to hear that"));
+        store.save_session(&session).unwrap();
+        let first = store.search(&SessionSearchQuery::new("broken")).unwrap();
+        assert_eq!(first.len(), 1);
+
+        // Compaction drops the first message; survivors keep their timestamps.
+        session.messages.remove(0);
+        session.messages.push(Message::user("Refund is fine"));
+        store.save_session(&session).unwrap();
+        assert!(store
+            .search(&SessionSearchQuery::new("broken"))
+            .unwrap()
+            .is_empty());
+        let sorry = store.search(&SessionSearchQuery::new("sorry")).unwrap();
+        assert_eq!(sorry[0].message_index, 0);
+
+        store.delete_session(session.id).unwrap();
+        assert!(store
+            .search(&SessionSearchQuery::new("refund"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_search_canonical_history_survives_compaction() {
+        let store = setup();
+        let agent_id = AgentId::new();
+        store
+            .append_canonical(agent_id, &[Message::user("My cat is named Miso")], None)
+            .unwrap();
+        let filler: Vec<Message> = (0..60)
+            .map(|i| Message::user(format!("filler {i}")))
+            .collect();
+        store.append_canonical(agent_id, &filler, Some(55)).unwrap();
+        assert!(store
+            .canonical_context(agent_id, None)
+            .unwrap()
+            .1
+            .iter()
+            .all(|m| !m.content.text_content().contains("Miso")));
+
+        let hits = store.search(&SessionSearchQuery::new("miso")).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].session_id, None);
+
+        // The same message in a regular session is reported once.
+        let mut session = store.create_session(agent_id).unwrap();
+        session.messages.push(Message::user("My cat is named Miso"));
+        store.save_session(&session).unwrap();
+        let hits = store.search(&SessionSearchQuery::new("miso")).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].session_id, Some(session.id));
+
+        store.delete_canonical_session(agent_id).unwrap();
+        store.delete_session(session.id).unwrap();
+        assert!(store
+            .search(&SessionSearchQuery::new("miso"))
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn test_jsonl_mirror_write() {
         let store = setup();
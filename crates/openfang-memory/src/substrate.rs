@@ -3,11 +3,12 @@
 //! Composes the structured store, semantic store, knowledge store,
 //! session store, and consolidation engine behind a single async API.
 
-use crate::consolidation::ConsolidationEngine;
+use crate::consolidation::{ConsolidationEngine, MemorySummarizer};
 use crate::knowledge::KnowledgeStore;
 use crate::migration::run_migrations;
+use crate::portability::{ExportOptions, MemorySnapshot, PortabilityStore};
 use crate::semantic::SemanticStore;
-use crate::session::{Session, SessionStore};
+use crate::session::{Session, SessionSearchHit, SessionSearchQuery, SessionStore};
 use crate::structured::StructuredStore;
 use crate::usage::UsageStore;
 
@@ -16,8 +17,9 @@ use openfang_types::agent::{AgentEntry, AgentId, SessionId};
 use openfang_types::config::MemoryConfig;
 use openfang_types::error::{OpenFangError, OpenFangResult};
 use openfang_types::memory::{
-    ConsolidationReport, Entity, ExportFormat, GraphMatch, GraphPattern, ImportReport, Memory,
-    MemoryFilter, MemoryFragment, MemoryId, MemorySource, Relation,
+    ConsolidationReport, Entity, ExportFormat, GraphMatch, GraphNeighborhood, GraphPath,
+    GraphPattern, ImportMode, ImportReport, Memory, MemoryFilter, MemoryFragment, MemoryId,
+    MemorySource, Relation, RelationType,
 };
 use rusqlite::Connection;
 use std::collections::HashMap;
@@ -35,6 +37,7 @@ pub struct MemorySubstrate {
     sessions: SessionStore,
     consolidation: ConsolidationEngine,
     usage: UsageStore,
+    portability: PortabilityStore,
 }
 
 impl MemorySubstrate {
@@ -54,7 +57,13 @@ impl MemorySubstrate {
         run_migrations(&conn).map_err(|e| OpenFangError::Memory(e.to_string()))?;
         let shared = Arc::new(Mutex::new(conn));
 
-        let semantic = Self::create_semantic_store(Arc::clone(&shared), memory_config);
+        let mut semantic = Self::create_semantic_store(Arc::clone(&shared), memory_config)
+            .with_keyword_weight(memory_config.hybrid_keyword_weight);
+        if memory_config.vector_index {
+            let mut index_path = db_path.as_os_str().to_owned();
+            index_path.push(".hnsw");
+            semantic = semantic.with_vector_index(Some(index_path.into()))?;
+        }
 
         Ok(Self {
             conn: Arc::clone(&shared),
@@ -63,7 +72,11 @@ impl MemorySubstrate {
             knowledge: KnowledgeStore::new(Arc::clone(&shared)),
             sessions: SessionStore::new(Arc::clone(&shared)),
             usage: UsageStore::new(Arc::clone(&shared)),
-            consolidation: ConsolidationEngine::new(shared, decay_rate),
+            portability: PortabilityStore::new(Arc::clone(&shared)),
+            consolidation: ConsolidationEngine::new(shared, decay_rate).with_similarity(
+                memory_config.consolidation_similarity,
+                memory_config.consolidation_text_similarity,
+            ),
         })
     }
 
@@ -113,10 +126,11 @@ impl MemorySubstrate {
         Ok(Self {
             conn: Arc::clone(&shared),
             structured: StructuredStore::new(Arc::clone(&shared)),
-            semantic: SemanticStore::new(Arc::clone(&shared)),
+            semantic: SemanticStore::new(Arc::clone(&shared)).with_vector_index(None)?,
             knowledge: KnowledgeStore::new(Arc::clone(&shared)),
             sessions: SessionStore::new(Arc::clone(&shared)),
             usage: UsageStore::new(Arc::clone(&shared)),
+            portability: PortabilityStore::new(Arc::clone(&shared)),
             consolidation: ConsolidationEngine::new(shared, decay_rate),
         })
     }
@@ -314,6 +328,25 @@ impl MemorySubstrate {
         Ok(())
     }
 
+    /// Full-text search over session and canonical transcripts.
+    pub fn session_search(
+        &self,
+        query: &SessionSearchQuery,
+    ) -> OpenFangResult<Vec<SessionSearchHit>> {
+        self.sessions.search(query)
+    }
+
+    /// Full-text transcript search on a blocking thread.
+    pub async fn session_search_async(
+        &self,
+        query: SessionSearchQuery,
+    ) -> OpenFangResult<Vec<SessionSearchHit>> {
+        let sessions = self.sessions.clone();
+        tokio::task::spawn_blocking(move || sessions.search(&query))
+            .await
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?
+    }
+
     // -----------------------------------------------------------------
     // Paired devices persistence
     // -----------------------------------------------------------------
@@ -462,6 +495,165 @@ impl MemorySubstrate {
         .map_err(|e| OpenFangError::Internal(e.to_string()))?
     }
 
+    // -----------------------------------------------------------------
+    // Consolidation
+    // -----------------------------------------------------------------
+
+    /// Run a consolidation cycle, letting `summarizer` write the merged text
+    /// for each cluster of near-duplicates.
+    ///
+    /// Clustering and merging run on blocking threads; only the summarizer
+    /// calls are awaited on the async runtime. A failed summary falls back to
+    /// the representative memory's text.
+    pub async fn consolidate_with_summarizer(
+        &self,
+        summarizer: Option<Arc<dyn MemorySummarizer>>,
+    ) -> OpenFangResult<ConsolidationReport> {
+        let start = std::time::Instant::now();
+        let engine = self.consolidation.clone();
+        let (decayed, clusters) = tokio::task::spawn_blocking(move || {
+            Ok::<_, OpenFangError>((engine.decay()?, engine.find_clusters()?))
+        })
+        .await
+        .map_err(|e| OpenFangError::Internal(e.to_string()))??;
+
+        let mut merged = 0u64;
+        for cluster in clusters {
+            let summary = match summarizer {
+                Some(ref s) => match s.summarize(&cluster.contents()).await {
+                    Ok(text) => Some(text),
+                    Err(e) => {
+                        warn!(agent = %cluster.agent_id, error = %e, "Merge summary failed, keeping representative text");
+                        None
+                    }
+                },
+                None => None,
+            };
+            let engine = self.consolidation.clone();
+            let count = cluster.members.len() as u64;
+            tokio::task::spawn_blocking(move || engine.apply_merge(&cluster, summary.as_deref()))
+                .await
+                .map_err(|e| OpenFangError::Internal(e.to_string()))??;
+            merged += count;
+        }
+        if merged > 0 {
+            self.sync_vector_index().await?;
+        }
+
+        Ok(ConsolidationReport {
+            memories_merged: merged,
+            memories_decayed: decayed,
+            duration_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Bring the vector index in line with the `memories` table.
+    pub async fn sync_vector_index(&self) -> OpenFangResult<usize> {
+        let semantic = self.semantic.clone();
+        tokio::task::spawn_blocking(move || semantic.sync_index())
+            .await
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?
+    }
+
+    /// Rebuild the vector index from the `memories` table.
+    pub async fn rebuild_vector_index(&self) -> OpenFangResult<usize> {
+        let semantic = self.semantic.clone();
+        tokio::task::spawn_blocking(move || semantic.rebuild_index())
+            .await
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?
+    }
+
+    /// Largest number of active memories held by any single agent.
+    pub fn max_agent_memory_count(&self) -> OpenFangResult<u64> {
+        self.consolidation.max_agent_memory_count()
+    }
+
+    // -----------------------------------------------------------------
+    // Knowledge graph traversal
+    // -----------------------------------------------------------------
+
+    /// Find multi-hop paths matching a graph pattern.
+    pub async fn traverse_graph(&self, pattern: GraphPattern) -> OpenFangResult<Vec<GraphPath>> {
+        let store = self.knowledge.clone();
+        tokio::task::spawn_blocking(move || store.traverse(pattern))
+            .await
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?
+    }
+
+    /// Find the shortest path between two entities (by ID or name).
+    pub async fn shortest_path(
+        &self,
+        from: &str,
+        to: &str,
+        max_depth: u32,
+        relation: Option<RelationType>,
+        min_confidence: f32,
+    ) -> OpenFangResult<Option<GraphPath>> {
+        let store = self.knowledge.clone();
+        let from = from.to_string();
+        let to = to.to_string();
+        tokio::task::spawn_blocking(move || {
+            store.shortest_path(&from, &to, max_depth, relation, min_confidence)
+        })
+        .await
+        .map_err(|e| OpenFangError::Internal(e.to_string()))?
+    }
+
+    /// Collect the entities and relations within `depth` hops of an entity.
+    pub async fn graph_neighborhood(
+        &self,
+        center: &str,
+        depth: u32,
+        relation: Option<RelationType>,
+        min_confidence: f32,
+        limit: usize,
+    ) -> OpenFangResult<Option<GraphNeighborhood>> {
+        let store = self.knowledge.clone();
+        let center = center.to_string();
+        tokio::task::spawn_blocking(move || {
+            store.neighborhood(&center, depth, relation, min_confidence, limit)
+        })
+        .await
+        .map_err(|e| OpenFangError::Internal(e.to_string()))?
+    }
+
+    // -----------------------------------------------------------------
+    // Export / import
+    // -----------------------------------------------------------------
+
+    /// Export memory data with explicit options (agent filter, sessions).
+    pub async fn export_with(
+        &self,
+        options: ExportOptions,
+        format: ExportFormat,
+    ) -> OpenFangResult<Vec<u8>> {
+        let store = self.portability.clone();
+        tokio::task::spawn_blocking(move || store.export(&options)?.encode(format))
+            .await
+            .map_err(|e| OpenFangError::Internal(e.to_string()))?
+    }
+
+    /// Import memory data with explicit merge/replace semantics.
+    pub async fn import_with(
+        &self,
+        data: &[u8],
+        format: ExportFormat,
+        mode: ImportMode,
+    ) -> OpenFangResult<ImportReport> {
+        let snapshot = MemorySnapshot::decode(data, format)?;
+        let store = self.portability.clone();
+        let semantic = self.semantic.clone();
+        tokio::task::spawn_blocking(move || {
+            let report = store.import(&snapshot, mode)?;
+            // Imports can overwrite embeddings in place, so rebuild rather
+            // than sync.
+            semantic.rebuild_index()?;
+            Ok(report)
+        })
+        .await
+        .map_err(|e| OpenFangError::Internal(e.to_string()))?
+    }
+
     // -----------------------------------------------------------------
     // Task queue operations
     // -----------------------------------------------------------------
@@ -708,23 +900,24 @@ impl Memory for MemorySubstrate {
 
     async fn consolidate(&self) -> OpenFangResult<ConsolidationReport> {
         let engine = self.consolidation.clone();
-        tokio::task::spawn_blocking(move || engine.consolidate())
-            .await
-            .map_err(|e| OpenFangError::Internal(e.to_string()))?
+        let semantic = self.semantic.clone();
+        tokio::task::spawn_blocking(move || {
+            let report = engine.consolidate()?;
+            if report.memories_merged > 0 {
+                semantic.sync_index()?;
+            }
+            Ok(report)
+        })
+        .await
+        .map_err(|e| OpenFangError::Internal(e.to_string()))?
     }
 
     async fn export(&self, format: ExportFormat) -> OpenFangResult<Vec<u8>> {
-        let _ = format;
-        Ok(Vec::new())
+        self.export_with(ExportOptions::default(), format).await
     }
 
-    async fn import(&self, _data: &[u8], _format: ExportFormat) -> OpenFangResult<ImportReport> {
-        Ok(ImportReport {
-            entities_imported: 0,
-            relations_imported: 0,
-            memories_imported: 0,
-            errors: vec!["Import not yet implemented in Phase 1".to_string()],
-        })
+    async fn import(&self, data: &[u8], format: ExportFormat) -> OpenFangResult<ImportReport> {
+        self.import_with(data, format, ImportMode::Merge).await
     }
 }
 
@@ -762,6 +955,35 @@ mod tests {
         assert_eq!(results.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_substrate_export_import_round_trip() {
+        let src = MemorySubstrate::open_in_memory(0.1).unwrap();
+        let agent_id = AgentId::new();
+        src.set(agent_id, "k", serde_json::json!({"n": 1}))
+            .await
+            .unwrap();
+        src.remember(
+            agent_id,
+            "Portable memory",
+            MemorySource::UserProvided,
+            "semantic",
+            HashMap::new(),
+        )
+        .await
+        .unwrap();
+        let data = src.export(ExportFormat::MessagePack).await.unwrap();
+
+        let dst = MemorySubstrate::open_in_memory(0.1).unwrap();
+        let report = dst.import(&data, ExportFormat::MessagePack).await.unwrap();
+        assert!(report.errors.is_empty());
+        assert_eq!(report.memories_imported, 1);
+        assert_eq!(
+            dst.get(agent_id, "k").await.unwrap(),
+            Some(serde_json::json!({"n": 1}))
+        );
+        assert_eq!(dst.recall("Portable", 10, None).await.unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_task_post_and_list() {
         let substrate = MemorySubstrate::open_in_memory(0.1).unwrap();
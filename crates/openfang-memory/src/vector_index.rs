@@ -0,0 +1,484 @@
+//! Approximate nearest-neighbor index over memory embeddings.
+//!
+//! A small in-process HNSW (Hierarchical Navigable Small World) graph keyed by
+//! memory ID. The `memories` table stays the source of truth: the index can be
+//! rebuilt from it at any time, and a persisted copy next to the SQLite file
+//! only saves that work on startup.
+//!
+//! Vectors are L2-normalized on insert so distance is `1 - dot(a, b)`.
+//! Removals leave tombstones that are still traversed but never returned;
+//! the graph is compacted once tombstones outnumber live entries.
+
+use openfang_types::error::{OpenFangError, OpenFangResult};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::path::Path;
+
+/// On-disk format version of a persisted index.
+const INDEX_FORMAT_VERSION: u32 = 1;
+
+/// Neighbors kept per node on upper layers (layer 0 keeps twice as many).
+const DEFAULT_M: usize = 16;
+
+/// Candidate list size while inserting.
+const DEFAULT_EF_CONSTRUCTION: usize = 100;
+
+/// Minimum candidate list size while searching.
+const DEFAULT_EF_SEARCH: usize = 64;
+
+/// Hard cap on layer count, whatever the random level draw says.
+const MAX_LEVEL: usize = 12;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Node {
+    id: String,
+    vector: Vec<f32>,
+    /// `neighbors[layer]` for every layer this node lives on.
+    neighbors: Vec<Vec<u32>>,
+    deleted: bool,
+}
+
+/// HNSW index mapping memory IDs to normalized embedding vectors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HnswIndex {
+    version: u32,
+    dim: Option<usize>,
+    m: usize,
+    ef_construction: usize,
+    nodes: Vec<Node>,
+    slots: HashMap<String, u32>,
+    entry: Option<u32>,
+    rng_state: u64,
+    tombstones: usize,
+}
+
+impl Default for HnswIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A search candidate ordered by distance.
+#[derive(Clone, Copy, PartialEq)]
+struct Candidate {
+    dist: f32,
+    slot: u32,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.dist
+            .total_cmp(&other.dist)
+            .then_with(|| self.slot.cmp(&other.slot))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl HnswIndex {
+    /// Create an empty index. The dimension is fixed by the first insert.
+    pub fn new() -> Self {
+        Self {
+            version: INDEX_FORMAT_VERSION,
+            dim: None,
+            m: DEFAULT_M,
+            ef_construction: DEFAULT_EF_CONSTRUCTION,
+            nodes: Vec::new(),
+            slots: HashMap::new(),
+            entry: None,
+            rng_state: 0x9E37_79B9_7F4A_7C15,
+            tombstones: 0,
+        }
+    }
+
+    /// Number of live (non-removed) vectors.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Whether the index holds no live vectors.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Dimension of indexed vectors, once the first one has been inserted.
+    pub fn dim(&self) -> Option<usize> {
+        self.dim
+    }
+
+    /// Whether a memory ID is indexed.
+    pub fn contains(&self, id: &str) -> bool {
+        self.slots.contains_key(id)
+    }
+
+    /// IDs of all live vectors.
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.slots.keys().map(|s| s.as_str())
+    }
+
+    /// Insert or replace the vector for a memory ID.
+    ///
+    /// Returns `false` (and indexes nothing) when the vector is empty, has
+    /// zero norm, or its dimension differs from the index's.
+    pub fn insert(&mut self, id: &str, vector: &[f32]) -> bool {
+        let Some(vector) = normalize(vector) else {
+            return false;
+        };
+        self.remove(id);
+        match self.dim {
+            Some(d) if d != vector.len() => return false,
+            Some(_) => {}
+            None => self.dim = Some(vector.len()),
+        }
+
+        let level = self.random_level();
+        let slot = self.nodes.len() as u32;
+        self.nodes.push(Node {
+            id: id.to_string(),
+            vector,
+            neighbors: vec![Vec::new(); level + 1],
+            deleted: false,
+        });
+        self.slots.insert(id.to_string(), slot);
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(slot);
+            return true;
+        };
+
+        let top = self.level_of(entry);
+        let query = self.nodes[slot as usize].vector.clone();
+        let mut ep = entry;
+        for layer in (level + 1..=top).rev() {
+            ep = self.greedy_closest(&query, ep, layer);
+        }
+        for layer in (0..=level.min(top)).rev() {
+            let found = self.search_layer(&query, &[ep], self.ef_construction, layer);
+            let max = self.max_neighbors(layer);
+            let selected: Vec<u32> = found.iter().take(max).map(|c| c.slot).collect();
+            self.nodes[slot as usize].neighbors[layer] = selected.clone();
+            for &n in &selected {
+                self.nodes[n as usize].neighbors[layer].push(slot);
+                if self.nodes[n as usize].neighbors[layer].len() > max {
+                    self.prune(n, layer, max);
+                }
+            }
+            if let Some(best) = found.first() {
+                ep = best.slot;
+            }
+        }
+        if level > top {
+            self.entry = Some(slot);
+        }
+        true
+    }
+
+    /// Remove a memory ID. Returns whether it was indexed.
+    pub fn remove(&mut self, id: &str) -> bool {
+        let Some(slot) = self.slots.remove(id) else {
+            return false;
+        };
+        self.nodes[slot as usize].deleted = true;
+        self.tombstones += 1;
+        if self.slots.is_empty() {
+            *self = Self {
+                rng_state: self.rng_state,
+                ..Self::new()
+            };
+        } else if self.tombstones > self.slots.len() {
+            self.compact();
+        }
+        true
+    }
+
+    /// Return up to `k` `(memory_id, cosine_similarity)` pairs, most similar
+    /// first. `ef` widens the search beam (higher = better recall, slower).
+    pub fn search(&self, query: &[f32], k: usize, ef: usize) -> Vec<(String, f32)> {
+        let (Some(entry), Some(query)) = (self.entry, normalize(query)) else {
+            return Vec::new();
+        };
+        if k == 0 || Some(query.len()) != self.dim {
+            return Vec::new();
+        }
+        let mut ep = entry;
+        for layer in (1..=self.level_of(entry)).rev() {
+            ep = self.greedy_closest(&query, ep, layer);
+        }
+        // Widen the beam by the tombstone count so removed nodes can't crowd
+        // out live results.
+        let ef = ef.max(k).max(DEFAULT_EF_SEARCH) + self.tombstones.min(k * 4);
+        self.search_layer(&query, &[ep], ef, 0)
+            .into_iter()
+            .filter(|c| !self.nodes[c.slot as usize].deleted)
+            .take(k)
+            .map(|c| (self.nodes[c.slot as usize].id.clone(), 1.0 - c.dist))
+            .collect()
+    }
+
+    /// Load a persisted index. Returns `None` when the file is missing,
+    /// unreadable, or written by an incompatible version.
+    pub fn load(path: &Path) -> Option<Self> {
+        let bytes = std::fs::read(path).ok()?;
+        let index: Self = rmp_serde::from_slice(&bytes).ok()?;
+        (index.version == INDEX_FORMAT_VERSION).then_some(index)
+    }
+
+    /// Persist the index atomically (write to a temp file, then rename).
+    pub fn save(&self, path: &Path) -> OpenFangResult<()> {
+        let bytes =
+            rmp_serde::to_vec(self).map_err(|e| OpenFangError::Serialization(e.to_string()))?;
+        let tmp = path.with_extension("hnsw.tmp");
+        std::fs::write(&tmp, bytes).map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        std::fs::rename(&tmp, path).map_err(|e| OpenFangError::Memory(e.to_string()))?;
+        Ok(())
+    }
+
+    fn level_of(&self, slot: u32) -> usize {
+        self.nodes[slot as usize].neighbors.len() - 1
+    }
+
+    fn max_neighbors(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.m * 2
+        } else {
+            self.m
+        }
+    }
+
+    fn distance(&self, query: &[f32], slot: u32) -> f32 {
+        let v = &self.nodes[slot as usize].vector;
+        1.0 - query.iter().zip(v).map(|(a, b)| a * b).sum::<f32>()
+    }
+
+    /// Draw a level from the usual exponential distribution (xorshift64*).
+    fn random_level(&mut self) -> usize {
+        let mut x = self.rng_state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.rng_state = x;
+        let r = x.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        let uniform = ((r >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+        let ml = 1.0 / (self.m as f64).ln();
+        ((-uniform.ln() * ml) as usize).min(MAX_LEVEL)
+    }
+
+    fn greedy_closest(&self, query: &[f32], start: u32, layer: usize) -> u32 {
+        let mut best = start;
+        let mut best_dist = self.distance(query, start);
+        loop {
+            let mut improved = false;
+            for &n in &self.nodes[best as usize].neighbors[layer] {
+                let d = self.distance(query, n);
+                if d < best_dist {
+                    best = n;
+                    best_dist = d;
+                    improved = true;
+                }
+            }
+            if !improved {
+                return best;
+            }
+        }
+    }
+
+    /// Beam search on one layer. Returns candidates sorted nearest first.
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entries: &[u32],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<u32> = entries.iter().copied().collect();
+        // Min-heap of candidates to expand, max-heap of current best results.
+        let mut frontier: BinaryHeap<std::cmp::Reverse<Candidate>> = BinaryHeap::new();
+        let mut best: BinaryHeap<Candidate> = BinaryHeap::new();
+        for &slot in entries {
+            let c = Candidate {
+                dist: self.distance(query, slot),
+                slot,
+            };
+            frontier.push(std::cmp::Reverse(c));
+            best.push(c);
+        }
+        while let Some(std::cmp::Reverse(current)) = frontier.pop() {
+            if best.len() >= ef && best.peek().is_some_and(|w| current.dist > w.dist) {
+                break;
+            }
+            let node = &self.nodes[current.slot as usize];
+            let Some(neighbors) = node.neighbors.get(layer) else {
+                continue;
+            };
+            for &n in neighbors {
+                if !visited.insert(n) {
+                    continue;
+                }
+                let c = Candidate {
+                    dist: self.distance(query, n),
+                    slot: n,
+                };
+                if best.len() < ef || best.peek().is_some_and(|w| c.dist < w.dist) {
+                    frontier.push(std::cmp::Reverse(c));
+                    best.push(c);
+                    if best.len() > ef {
+                        best.pop();
+                    }
+                }
+            }
+        }
+        best.into_sorted_vec()
+    }
+
+    /// Keep only the `max` nearest neighbors of a node on a layer.
+    fn prune(&mut self, slot: u32, layer: usize, max: usize) {
+        let base = self.nodes[slot as usize].vector.clone();
+        let mut scored: Vec<Candidate> = self.nodes[slot as usize].neighbors[layer]
+            .iter()
+            .map(|&n| Candidate {
+                dist: self.distance(&base, n),
+                slot: n,
+            })
+            .collect();
+        scored.sort();
+        scored.dedup_by_key(|c| c.slot);
+        scored.truncate(max);
+        self.nodes[slot as usize].neighbors[layer] = scored.into_iter().map(|c| c.slot).collect();
+    }
+
+    /// Rebuild the graph from live nodes, dropping tombstones.
+    fn compact(&mut self) {
+        let live: Vec<Node> = self.nodes.iter().filter(|n| !n.deleted).cloned().collect();
+        let rng_state = self.rng_state;
+        *self = Self {
+            rng_state,
+            ..Self::new()
+        };
+        for node in live {
+            self.insert(&node.id, &node.vector);
+        }
+    }
+}
+
+/// L2-normalize a vector; `None` for empty or zero-norm input.
+fn normalize(v: &[f32]) -> Option<Vec<f32>> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if v.is_empty() || norm == 0.0 || !norm.is_finite() {
+        return None;
+    }
+    Some(v.iter().map(|x| x / norm).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random vectors for tests.
+    fn vectors(n: usize, dim: usize) -> Vec<Vec<f32>> {
+        let mut state = 42u64;
+        (0..n)
+            .map(|_| {
+                (0..dim)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        ((state >> 33) as f32 / u32::MAX as f32) - 0.25
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn brute_force(data: &[Vec<f32>], query: &[f32], k: usize) -> Vec<String> {
+        let q = normalize(query).unwrap();
+        let mut scored: Vec<(usize, f32)> = data
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let v = normalize(v).unwrap();
+                (i, q.iter().zip(&v).map(|(a, b)| a * b).sum())
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored
+            .into_iter()
+            .take(k)
+            .map(|(i, _)| format!("m{i}"))
+            .collect()
+    }
+
+    #[test]
+    fn test_search_recall_against_brute_force() {
+        let data = vectors(2000, 32);
+        let mut index = HnswIndex::new();
+        for (i, v) in data.iter().enumerate() {
+            assert!(index.insert(&format!("m{i}"), v));
+        }
+        assert_eq!(index.len(), 2000);
+
+        let queries = vectors(20, 32);
+        let mut hits = 0;
+        for q in &queries {
+            let expected: HashSet<String> = brute_force(&data, q, 10).into_iter().collect();
+            let got = index.search(q, 10, 64);
+            assert_eq!(got.len(), 10);
+            hits += got.iter().filter(|(id, _)| expected.contains(id)).count();
+        }
+        // HNSW is approximate; expect at least 90% recall@10.
+        assert!(hits >= 180, "recall too low: {hits}/200");
+    }
+
+    #[test]
+    fn test_remove_update_and_dimension_guard() {
+        let mut index = HnswIndex::new();
+        index.insert("a", &[1.0, 0.0, 0.0]);
+        index.insert("b", &[0.0, 1.0, 0.0]);
+        index.insert("c", &[0.9, 0.1, 0.0]);
+        assert!(!index.insert("bad", &[1.0, 0.0]));
+        assert!(!index.insert("zero", &[0.0, 0.0, 0.0]));
+
+        assert_eq!(index.search(&[1.0, 0.0, 0.0], 1, 10)[0].0, "a");
+        assert!(index.remove("a"));
+        assert!(!index.contains("a"));
+        assert_eq!(index.search(&[1.0, 0.0, 0.0], 1, 10)[0].0, "c");
+
+        // Re-inserting replaces the vector.
+        index.insert("b", &[1.0, 0.0, 0.0]);
+        let top = index.search(&[1.0, 0.0, 0.0], 1, 10);
+        assert_eq!(top[0].0, "b");
+        assert!((top[0].1 - 1.0).abs() < 1e-5);
+        assert_eq!(index.len(), 2);
+    }
+
+    #[test]
+    fn test_compaction_and_persistence() {
+        let data = vectors(200, 8);
+        let mut index = HnswIndex::new();
+        for (i, v) in data.iter().enumerate() {
+            index.insert(&format!("m{i}"), v);
+        }
+        for i in 0..150 {
+            index.remove(&format!("m{i}"));
+        }
+        assert_eq!(index.len(), 50);
+        assert!(index.tombstones <= index.len());
+        let got = index.search(&data[199], 5, 32);
+        assert_eq!(got[0].0, "m199");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.db.hnsw");
+        index.save(&path).unwrap();
+        let loaded = HnswIndex::load(&path).unwrap();
+        assert_eq!(loaded.len(), 50);
+        assert_eq!(loaded.search(&data[199], 1, 32)[0].0, "m199");
+        assert!(HnswIndex::load(&dir.path().join("missing.hnsw")).is_none());
+    }
+}
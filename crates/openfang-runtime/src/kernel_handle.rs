@@ -49,6 +49,31 @@ pub trait KernelHandle: Send + Sync {
     /// Recall a value from shared memory.
     fn memory_recall(&self, key: &str) -> Result<Option<serde_json::Value>, String>;
 
+    /// Semantic recall over an agent's own memories, honoring the filter's
+    /// time bounds and metadata predicates. `filter.agent_id` is overridden
+    /// with `agent_id`.
+    async fn memory_search(
+        &self,
+        agent_id: &str,
+        query: &str,
+        limit: usize,
+        filter: openfang_types::memory::MemoryFilter,
+    ) -> Result<Vec<openfang_types::memory::MemoryFragment>, String> {
+        let _ = (agent_id, query, limit, filter);
+        Err("Semantic memory search not available".to_string())
+    }
+
+    /// Full-text search over an agent's own session transcripts.
+    /// `query.agent_id` is overridden with `agent_id`.
+    async fn session_search(
+        &self,
+        agent_id: &str,
+        query: openfang_memory::session::SessionSearchQuery,
+    ) -> Result<Vec<openfang_memory::session::SessionSearchHit>, String> {
+        let _ = (agent_id, query);
+        Err("Session search not available".to_string())
+    }
+
     /// Find agents by query (matches on name substring, tag, or tool name; case-insensitive).
     fn find_agents(&self, query: &str) -> Vec<AgentInfo>;
 
@@ -95,6 +120,41 @@ pub trait KernelHandle: Send + Sync {
         pattern: openfang_types::memory::GraphPattern,
     ) -> Result<Vec<openfang_types::memory::GraphMatch>, String>;
 
+    /// Find multi-hop paths in the knowledge graph matching a pattern.
+    async fn knowledge_traverse(
+        &self,
+        pattern: openfang_types::memory::GraphPattern,
+    ) -> Result<Vec<openfang_types::memory::GraphPath>, String> {
+        let _ = pattern;
+        Err("Knowledge graph traversal not available".to_string())
+    }
+
+    /// Find the shortest path between two knowledge graph entities.
+    async fn knowledge_shortest_path(
+        &self,
+        from: &str,
+        to: &str,
+        max_depth: u32,
+        relation: Option<openfang_types::memory::RelationType>,
+        min_confidence: f32,
+    ) -> Result<Option<openfang_types::memory::GraphPath>, String> {
+        let _ = (from, to, max_depth, relation, min_confidence);
+        Err("Knowledge graph traversal not available".to_string())
+    }
+
+    /// Collect the knowledge graph neighborhood of an entity.
+    async fn knowledge_neighborhood(
+        &self,
+        center: &str,
+        depth: u32,
+        relation: Option<openfang_types::memory::RelationType>,
+        min_confidence: f32,
+        limit: usize,
+    ) -> Result<Option<openfang_types::memory::GraphNeighborhood>, String> {
+        let _ = (center, depth, relation, min_confidence, limit);
+        Err("Knowledge graph traversal not available".to_string())
+    }
+
     /// Create a cron job for the calling agent.
     async fn cron_create(
         &self,
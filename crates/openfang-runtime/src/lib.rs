@@ -51,6 +51,7 @@ pub mod think_filter;
 pub mod tool_policy;
 pub mod tool_runner;
 pub mod tts;
+pub mod wasm_skill;
 pub mod web_cache;
 pub mod web_content;
 pub mod web_fetch;
@@ -302,7 +302,8 @@ pub async fn execute_tool(
 
         // Shared memory tools
         "memory_store" => tool_memory_store(input, kernel),
-        "memory_recall" => tool_memory_recall(input, kernel),
+        "memory_recall" => tool_memory_recall(input, kernel, caller_agent_id).await,
+        "session_search" => tool_session_search(input, kernel, caller_agent_id).await,
 
         // Collaboration tools
         "agent_find" => tool_agent_find(input, kernel),
@@ -514,14 +515,31 @@ pub async fn execute_tool(
             else if let Some(registry) = skill_registry {
                 if let Some(skill) = registry.find_tool_provider(other) {
                     debug!(tool = other, skill = %skill.manifest.skill.name, "Dispatching to skill");
-                    match openfang_skills::loader::execute_skill_tool(
-                        &skill.manifest,
-                        &skill.path,
-                        other,
-                        input,
-                    )
-                    .await
+                    let outcome = if skill.manifest.runtime.runtime_type
+                        == openfang_skills::SkillRuntime::Wasm
                     {
+                        crate::wasm_skill::execute_wasm_skill(
+                            &skill.manifest,
+                            &skill.path,
+                            other,
+                            input,
+                            kernel.cloned(),
+                            caller_agent_id.unwrap_or_default(),
+                            web_ctx
+                                .map(|w| w.fetch.ssrf_allowed_hosts().to_vec())
+                                .unwrap_or_default(),
+                        )
+                        .await
+                    } else {
+                        openfang_skills::loader::execute_skill_tool(
+                            &skill.manifest,
+                            &skill.path,
+                            other,
+                            input,
+                        )
+                        .await
+                    };
+                    match outcome {
                         Ok(skill_result) => {
                             let content = serde_json::to_string(&skill_result.output)
                                 .unwrap_or_else(|_| skill_result.output.to_string());
@@ -709,13 +727,37 @@ pub fn builtin_tool_definitions() -> Vec<ToolDefinition> {
         },
         ToolDefinition {
             name: "memory_recall".to_string(),
-            description: "Recall a value from shared memory by key.".to_string(),
+            description: "Recall a value from shared memory by key, or search your own long-term memories with 'query'. Searches can be bounded in time (after/before) and filtered on memory metadata.".to_string(),
             input_schema: serde_json::json!({
                 "type": "object",
                 "properties": {
-                    "key": { "type": "string", "description": "The storage key to recall" }
+                    "key": { "type": "string", "description": "The storage key to recall (shared key-value memory)" },
+                    "query": { "type": "string", "description": "Search your memories instead of recalling a key (empty string lists recent memories)" },
+                    "limit": { "type": "integer", "description": "Maximum memories to return for a search (default: 10)" },
+                    "after": { "type": "string", "description": "Only memories created after this time: RFC 3339 timestamp or relative age like '7d', '24h', '30m'" },
+                    "before": { "type": "string", "description": "Only memories created before this time: RFC 3339 timestamp or relative age" },
+                    "scope": { "type": "string", "description": "Only memories in this scope (optional)" },
+                    "min_confidence": { "type": "number", "description": "Minimum memory confidence 0.0-1.0 (optional)" },
+                    "metadata": { "type": "object", "description": "Metadata keys that must equal the given values" },
+                    "metadata_in": { "type": "object", "description": "Metadata keys mapped to arrays of accepted values" },
+                    "metadata_exists": { "type": "array", "items": { "type": "string" }, "description": "Metadata keys that must be present" },
+                    "metadata_missing": { "type": "array", "items": { "type": "string" }, "description": "Metadata keys that must be absent" }
+                }
+            }),
+        },
+        ToolDefinition {
+            name: "session_search".to_string(),
+            description: "Full-text search over your past conversations. Use to recall what was said in earlier sessions, e.g. what a user told you about a topic last month.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Words to search for (all must appear in a message)" },
+                    "role": { "type": "string", "enum": ["user", "assistant", "system"], "description": "Only messages from this sender (optional)" },
+                    "after": { "type": "string", "description": "Only messages after this time: RFC 3339 timestamp or relative age like '30d', '24h'" },
+                    "before": { "type": "string", "description": "Only messages before this time: RFC 3339 timestamp or relative age" },
+                    "limit": { "type": "integer", "description": "Maximum results (default: 10)" }
                 },
-                "required": ["key"]
+                "required": ["query"]
             }),
         },
         // --- Collaboration tools ---
@@ -849,14 +891,30 @@ pub fn builtin_tool_definitions() -> Vec<ToolDefinition> {
         },
         ToolDefinition {
             name: "knowledge_query".to_string(),
-            description: "Query the knowledge graph. Filter by source entity, relation type, and/or target entity. Returns matching entity-relation-entity triples.".to_string(),
+            description: "Query the knowledge graph. mode 'match' (default) returns entity-relation-entity triples; 'paths' returns multi-hop paths up to max_depth (e.g. target='X', max_depth=2, hops=[{entity_type:'project'},{relation:'uses'}] finds who works on projects that use X); 'shortest_path' connects source and target; 'neighborhood' lists everything within max_depth hops of source.".to_string(),
             input_schema: serde_json::json!({
                 "type": "object",
                 "properties": {
-                    "source": { "type": "string", "description": "Filter by source entity name or ID (optional)" },
-                    "relation": { "type": "string", "description": "Filter by relation type (optional)" },
-                    "target": { "type": "string", "description": "Filter by target entity name or ID (optional)" },
-                    "max_depth": { "type": "integer", "description": "Maximum traversal depth (default: 1)" }
+                    "mode": { "type": "string", "enum": ["match", "paths", "shortest_path", "neighborhood"], "description": "Query mode (default: match)" },
+                    "source": { "type": "string", "description": "Filter by source entity name or ID (optional; required for shortest_path and neighborhood)" },
+                    "relation": { "type": "string", "description": "Filter by relation type on every hop (optional)" },
+                    "target": { "type": "string", "description": "Filter by target entity name or ID (optional; required for shortest_path)" },
+                    "entity_type": { "type": "string", "description": "Type every entity reached by a hop must have (optional)" },
+                    "hops": {
+                        "type": "array",
+                        "description": "Per-hop filters, first hop first (optional)",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "relation": { "type": "string" },
+                                "entity_type": { "type": "string" }
+                            }
+                        }
+                    },
+                    "max_depth": { "type": "integer", "description": "Maximum traversal depth (default: 1, or 4 for shortest_path; max 6)" },
+                    "min_confidence": { "type": "number", "description": "Ignore relations below this confidence (default: 0)" },
+                    "limit": { "type": "integer", "description": "Maximum results (default: 100)" },
+                    "offset": { "type": "integer", "description": "Results to skip, for pagination (default: 0)" }
                 }
             }),
         },
@@ -1711,11 +1769,17 @@ fn tool_memory_store(
     Ok(format!("Stored value under key '{key}'."))
 }
 
-fn tool_memory_recall(
+async fn tool_memory_recall(
     input: &serde_json::Value,
     kernel: Option<&Arc<dyn KernelHandle>>,
+    caller_agent_id: Option<&str>,
 ) -> Result<String, String> {
     let kh = require_kernel(kernel)?;
+    if input.get("key").is_none() {
+        if let Some(query) = input["query"].as_str() {
+            return tool_memory_search(input, kh, query, caller_agent_id).await;
+        }
+    }
     let key = input["key"].as_str().ok_or("Missing 'key' parameter")?;
     match kh.memory_recall(key)? {
         Some(val) => Ok(serde_json::to_string_pretty(&val).unwrap_or_else(|_| val.to_string())),
@@ -1723,6 +1787,146 @@ fn tool_memory_recall(
     }
 }
 
+async fn tool_memory_search(
+    input: &serde_json::Value,
+    kh: &Arc<dyn KernelHandle>,
+    query: &str,
+    caller_agent_id: Option<&str>,
+) -> Result<String, String> {
+    use openfang_types::memory::{MemoryFilter, MetadataPredicate};
+    let agent_id = caller_agent_id.ok_or("Memory search requires a calling agent")?;
+    let limit = input["limit"].as_u64().unwrap_or(10).clamp(1, 100) as usize;
+
+    let mut filter = MemoryFilter {
+        scope: input["scope"].as_str().map(|s| s.to_string()),
+        min_confidence: input["min_confidence"].as_f64().map(|c| c as f32),
+        after: input["after"].as_str().map(parse_time_bound).transpose()?,
+        before: input["before"].as_str().map(parse_time_bound).transpose()?,
+        ..Default::default()
+    };
+    if let Some(obj) = input["metadata"].as_object() {
+        filter.metadata = obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    }
+    if let Some(obj) = input["metadata_in"].as_object() {
+        for (key, values) in obj {
+            let values = values
+                .as_array()
+                .ok_or_else(|| format!("metadata_in.{key} must be an array"))?;
+            filter.metadata_predicates.push(MetadataPredicate::In {
+                key: key.clone(),
+                values: values.clone(),
+            });
+        }
+    }
+    for (field, exists) in [("metadata_exists", true), ("metadata_missing", false)] {
+        for key in input[field].as_array().into_iter().flatten() {
+            let key = key
+                .as_str()
+                .ok_or_else(|| format!("{field} must be an array of strings"))?;
+            filter.metadata_predicates.push(MetadataPredicate::Exists {
+                key: key.to_string(),
+                exists,
+            });
+        }
+    }
+
+    let memories = kh.memory_search(agent_id, query, limit, filter).await?;
+    if memories.is_empty() {
+        return Ok("No matching memories found.".to_string());
+    }
+    let mut output = format!("Found {} memor(ies):\n", memories.len());
+    for m in &memories {
+        output.push_str(&format!(
+            "\n  [{}] ({}, {:.0}%) {}",
+            m.created_at.format("%Y-%m-%d %H:%M"),
+            m.scope,
+            m.confidence * 100.0,
+            m.content
+        ));
+        if !m.metadata.is_empty() {
+            output.push_str(&format!(
+                " {}",
+                serde_json::to_string(&m.metadata).unwrap_or_default()
+            ));
+        }
+    }
+    Ok(output)
+}
+
+async fn tool_session_search(
+    input: &serde_json::Value,
+    kernel: Option<&Arc<dyn KernelHandle>>,
+    caller_agent_id: Option<&str>,
+) -> Result<String, String> {
+    use openfang_memory::session::SessionSearchQuery;
+    use openfang_types::message::Role;
+    let kh = require_kernel(kernel)?;
+    let agent_id = caller_agent_id.ok_or("Session search requires a calling agent")?;
+    let text = input["query"].as_str().ok_or("Missing 'query' parameter")?;
+    let role = match input["role"].as_str() {
+        None => None,
+        Some("user") => Some(Role::User),
+        Some("assistant") => Some(Role::Assistant),
+        Some("system") => Some(Role::System),
+        Some(other) => return Err(format!("Invalid role '{other}'")),
+    };
+    let query = SessionSearchQuery {
+        role,
+        after: input["after"].as_str().map(parse_time_bound).transpose()?,
+        before: input["before"].as_str().map(parse_time_bound).transpose()?,
+        limit: input["limit"].as_u64().unwrap_or(10).clamp(1, 50) as usize,
+        highlight_start: "**".to_string(),
+        highlight_end: "**".to_string(),
+        ..SessionSearchQuery::new(text)
+    };
+
+    let hits = kh.session_search(agent_id, query).await?;
+    if hits.is_empty() {
+        return Ok(format!("No past messages found matching '{text}'."));
+    }
+    let mut output = format!("Found {} message(s):\n", hits.len());
+    for hit in &hits {
+        let date = hit.created_at.get(..16).unwrap_or(&hit.created_at);
+        let session = match (&hit.label, hit.session_id) {
+            (Some(label), _) => label.clone(),
+            (None, Some(id)) => id.to_string(),
+            (None, None) => "canonical".to_string(),
+        };
+        let role = match hit.role {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+            Role::System => "system",
+        };
+        output.push_str(&format!(
+            "\n  [{}] {role} in {session}: {}",
+            date.replace('T', " "),
+            hit.snippet
+        ));
+    }
+    Ok(output)
+}
+
+/// Parse a recall time bound: an RFC 3339 timestamp, or a relative age such
+/// as `30m`, `24h`, `7d` or `2w` meaning that long before now.
+fn parse_time_bound(s: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    let s = s.trim();
+    if let Ok(t) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Ok(t.with_timezone(&chrono::Utc));
+    }
+    let invalid = || format!("Invalid time '{s}': use RFC 3339 or a relative age like '7d'");
+    let last = s.chars().last().ok_or_else(invalid)?;
+    let (num, unit) = s.split_at(s.len() - last.len_utf8());
+    let n: i64 = num.trim().parse().map_err(|_| invalid())?;
+    let age = match unit {
+        "m" => chrono::Duration::minutes(n),
+        "h" => chrono::Duration::hours(n),
+        "d" => chrono::Duration::days(n),
+        "w" => chrono::Duration::weeks(n),
+        _ => return Err(invalid()),
+    };
+    Ok(chrono::Utc::now() - age)
+}
+
 // ---------------------------------------------------------------------------
 // Collaboration tools
 // ---------------------------------------------------------------------------
@@ -1937,15 +2141,113 @@ async fn tool_knowledge_query(
     let source = input["source"].as_str().map(|s| s.to_string());
     let target = input["target"].as_str().map(|s| s.to_string());
     let relation = input["relation"].as_str().map(parse_relation_type);
-    let max_depth = input["max_depth"].as_u64().unwrap_or(1) as u32;
+    let min_confidence = input["min_confidence"].as_f64().unwrap_or(0.0) as f32;
+    let limit = input["limit"].as_u64().unwrap_or(0) as usize;
+    let mode = input["mode"].as_str().unwrap_or("match");
+
+    match mode {
+        "shortest_path" => {
+            let from = source.ok_or("Missing 'source' parameter")?;
+            let to = target.ok_or("Missing 'target' parameter")?;
+            let max_depth = input["max_depth"].as_u64().unwrap_or(4) as u32;
+            let path = kh
+                .knowledge_shortest_path(&from, &to, max_depth, relation, min_confidence)
+                .await?;
+            return Ok(match path {
+                Some(p) => format!(
+                    "Shortest path ({} hop(s), {:.0}% confidence):\n  {}",
+                    p.len(),
+                    p.confidence * 100.0,
+                    format_graph_path(&p)
+                ),
+                None => {
+                    format!("No path found between '{from}' and '{to}' within {max_depth} hop(s).")
+                }
+            });
+        }
+        "neighborhood" => {
+            let center = source.ok_or("Missing 'source' parameter")?;
+            let depth = input["max_depth"].as_u64().unwrap_or(1) as u32;
+            let Some(n) = kh
+                .knowledge_neighborhood(&center, depth, relation, min_confidence, limit)
+                .await?
+            else {
+                return Ok(format!("Entity '{center}' not found."));
+            };
+            let names: std::collections::HashMap<&str, &str> = std::iter::once(&n.center)
+                .chain(n.entities.iter().map(|(e, _)| e))
+                .map(|e| (e.id.as_str(), e.name.as_str()))
+                .collect();
+            let mut output = format!(
+                "Neighborhood of {} ({:?}): {} entit(ies), {} relation(s)\n",
+                n.center.name,
+                n.center.entity_type,
+                n.entities.len(),
+                n.relations.len()
+            );
+            for (e, d) in &n.entities {
+                output.push_str(&format!(
+                    "\n  [{d} hop(s)] {} ({:?})",
+                    e.name, e.entity_type
+                ));
+            }
+            if !n.relations.is_empty() {
+                output.push('\n');
+            }
+            for r in &n.relations {
+                output.push_str(&format!(
+                    "\n  {} --[{:?} ({:.0}%)]--> {}",
+                    names.get(r.source.as_str()).unwrap_or(&r.source.as_str()),
+                    r.relation,
+                    r.confidence * 100.0,
+                    names.get(r.target.as_str()).unwrap_or(&r.target.as_str()),
+                ));
+            }
+            return Ok(output);
+        }
+        "match" | "paths" => {}
+        other => return Err(format!("Unknown mode '{other}'")),
+    }
 
+    let hops = input["hops"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .map(|h| openfang_types::memory::HopFilter {
+                    relation: h["relation"].as_str().map(parse_relation_type),
+                    entity_type: h["entity_type"].as_str().map(parse_entity_type),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
     let pattern = openfang_types::memory::GraphPattern {
         source,
         relation,
         target,
-        max_depth,
+        max_depth: input["max_depth"].as_u64().unwrap_or(1) as u32,
+        entity_type: input["entity_type"].as_str().map(parse_entity_type),
+        hops,
+        min_confidence,
+        limit,
+        offset: input["offset"].as_u64().unwrap_or(0) as usize,
     };
 
+    if mode == "paths" {
+        let paths = kh.knowledge_traverse(pattern).await?;
+        if paths.is_empty() {
+            return Ok("No matching knowledge graph paths found.".to_string());
+        }
+        let mut output = format!("Found {} path(s):\n", paths.len());
+        for p in &paths {
+            output.push_str(&format!(
+                "\n  [{:.0}%] {}",
+                p.confidence * 100.0,
+                format_graph_path(p)
+            ));
+        }
+        return Ok(output);
+    }
+
     let matches = kh.knowledge_query(pattern).await?;
     if matches.is_empty() {
         return Ok("No matching knowledge graph entries found.".to_string());
@@ -1966,6 +2268,24 @@ async fn tool_knowledge_query(
     Ok(output)
 }
 
+/// Render a path as `A (Person) --[WorksAt]--> B (Organization) <--[Uses]-- C`,
+/// with arrows showing each relation's stored direction.
+fn format_graph_path(path: &openfang_types::memory::GraphPath) -> String {
+    let mut out = String::new();
+    for (i, entity) in path.entities.iter().enumerate() {
+        if i > 0 {
+            let rel = &path.relations[i - 1];
+            if rel.source == entity.id {
+                out.push_str(&format!(" <--[{:?}]-- ", rel.relation));
+            } else {
+                out.push_str(&format!(" --[{:?}]--> ", rel.relation));
+            }
+        }
+        out.push_str(&format!("{} ({:?})", entity.name, entity.entity_type));
+    }
+    out
+}
+
 // ---------------------------------------------------------------------------
 // Scheduling tools
 // ---------------------------------------------------------------------------
@@ -3422,6 +3742,7 @@ mod tests {
         assert!(names.contains(&"agent_kill"));
         assert!(names.contains(&"memory_store"));
         assert!(names.contains(&"memory_recall"));
+        assert!(names.contains(&"session_search"));
         // 6 collaboration tools
         assert!(names.contains(&"agent_find"));
         assert!(names.contains(&"task_post"));
@@ -3829,6 +4150,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_format_graph_path_shows_relation_direction() {
+        use openfang_types::memory::{Entity, EntityType, GraphPath, Relation, RelationType};
+        let entity = |id: &str, entity_type| Entity {
+            id: id.to_string(),
+            entity_type,
+            name: id.to_string(),
+            properties: Default::default(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        let relation = |source: &str, relation, target: &str| Relation {
+            source: source.to_string(),
+            relation,
+            target: target.to_string(),
+            properties: Default::default(),
+            confidence: 1.0,
+            created_at: chrono::Utc::now(),
+        };
+        let path = GraphPath {
+            entities: vec![
+                entity("alice", EntityType::Person),
+                entity("apollo", EntityType::Project),
+                entity("hermes", EntityType::Project),
+            ],
+            relations: vec![
+                relation("alice", RelationType::WorksAt, "apollo"),
+                relation("hermes", RelationType::DependsOn, "apollo"),
+            ],
+            confidence: 1.0,
+        };
+        assert_eq!(
+            format_graph_path(&path),
+            "alice (Person) --[WorksAt]--> apollo (Project) <--[DependsOn]-- hermes (Project)"
+        );
+    }
+
+    #[test]
+    fn test_parse_time_bound() {
+        let t = parse_time_bound("2026-01-02T03:04:05Z").unwrap();
+        assert_eq!(t.to_rfc3339(), "2026-01-02T03:04:05+00:00");
+        let week = parse_time_bound("7d").unwrap();
+        let expected = chrono::Utc::now() - chrono::Duration::days(7);
+        assert!((week - expected).num_seconds().abs() < 5);
+        assert!(parse_time_bound("2h").unwrap() > week);
+        assert!(parse_time_bound("7y").is_err());
+        assert!(parse_time_bound("soon").is_err());
+        assert!(parse_time_bound("").is_err());
+        assert!(parse_time_bound("5é").is_err());
+    }
+
     // --- Schedule parser tests ---
     #[test]
     fn test_parse_schedule_every_minutes() {
@@ -4292,6 +4664,7 @@ mod tests {
         created: std::sync::Mutex<Vec<(String, serde_json::Value)>>,
         cancelled: std::sync::Mutex<Vec<String>>,
         jobs: std::sync::Mutex<Vec<serde_json::Value>>,
+        searches: std::sync::Mutex<Vec<(String, openfang_memory::session::SessionSearchQuery)>>,
     }
 
     impl FakeKernelHandle {
@@ -4300,6 +4673,7 @@ mod tests {
                 created: std::sync::Mutex::new(Vec::new()),
                 cancelled: std::sync::Mutex::new(Vec::new()),
                 jobs: std::sync::Mutex::new(Vec::new()),
+                searches: std::sync::Mutex::new(Vec::new()),
             }
         }
 
@@ -4404,6 +4778,53 @@ mod tests {
             self.cancelled.lock().unwrap().push(job_id.to_string());
             Ok(())
         }
+
+        async fn session_search(
+            &self,
+            agent_id: &str,
+            query: openfang_memory::session::SessionSearchQuery,
+        ) -> Result<Vec<openfang_memory::session::SessionSearchHit>, String> {
+            self.searches
+                .lock()
+                .unwrap()
+                .push((agent_id.to_string(), query));
+            Ok(vec![openfang_memory::session::SessionSearchHit {
+                session_id: None,
+                agent_id: openfang_types::agent::AgentId::new(),
+                label: Some("support".to_string()),
+                message_index: 3,
+                role: openfang_types::message::Role::User,
+                snippet: "my **router** is a Netgear".to_string(),
+                created_at: "2026-09-12T08:30:00+00:00".to_string(),
+                score: 1.5,
+            }])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_search_scopes_to_caller() {
+        let fake = Arc::new(FakeKernelHandle::new());
+        let handle: Arc<dyn crate::kernel_handle::KernelHandle> = fake.clone();
+        let input = serde_json::json!({ "query": "router", "role": "user", "after": "30d" });
+
+        let out = tool_session_search(&input, Some(&handle), Some("aaa"))
+            .await
+            .unwrap();
+        assert!(out.contains("[2026-09-12 08:30] user in support: my **router** is a Netgear"));
+
+        let bad = serde_json::json!({ "query": "router", "role": "tool" });
+        assert!(tool_session_search(&bad, Some(&handle), Some("aaa"))
+            .await
+            .is_err());
+
+        let searches = fake.searches.lock().unwrap();
+        assert_eq!(searches[0].0, "aaa");
+        assert_eq!(searches[0].1.query, "router");
+        assert_eq!(
+            searches[0].1.role,
+            Some(openfang_types::message::Role::User)
+        );
+        assert!(searches[0].1.after.is_some());
     }
 
     #[tokio::test]
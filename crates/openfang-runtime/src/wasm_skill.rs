@@ -0,0 +1,333 @@
+//! WASM skill runtime — runs `runtime.type = "wasm"` skills in the [`WasmSandbox`].
+//!
+//! The skill's module is loaded from its directory and executed with the
+//! sandbox guest ABI (see [`crate::sandbox`]). The guest receives
+//! `{"tool": "<name>", "input": {...}}` — the same payload the Python and
+//! Node runtimes get on stdin — and returns JSON. A returned object with an
+//! `"error"` key is reported as a failed tool call.
+//!
+//! Unlike the subprocess runtimes, host access is deny-by-default: the guest
+//! only gets the capabilities listed in the skill's
+//! `[requirements] capabilities`, and every call is bounded by fuel and an
+//! epoch deadline.
+
+use crate::kernel_handle::KernelHandle;
+use crate::sandbox::{SandboxConfig, WasmSandbox};
+use openfang_skills::{SkillError, SkillManifest, SkillToolResult};
+use openfang_types::capability::Capability;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use tracing::debug;
+
+/// Fuel budget for a single skill tool call (matches the default agent CPU
+/// budget of 30s at 100k fuel per millisecond).
+pub const SKILL_FUEL_LIMIT: u64 = 3_000_000_000;
+
+/// Wall-clock limit for a single skill tool call.
+pub const SKILL_TIMEOUT_SECS: u64 = 30;
+
+/// Engine shared by all skill invocations; creating one is expensive.
+fn shared_sandbox() -> Result<&'static WasmSandbox, SkillError> {
+    static SANDBOX: OnceLock<Result<WasmSandbox, String>> = OnceLock::new();
+    SANDBOX
+        .get_or_init(|| WasmSandbox::new().map_err(|e| e.to_string()))
+        .as_ref()
+        .map_err(|e| SkillError::RuntimeNotAvailable(format!("WASM engine unavailable: {e}")))
+}
+
+/// Parse a skill's declared capabilities into sandbox grants.
+///
+/// An unrecognised capability string is an error rather than being dropped,
+/// so a typo cannot silently change what a skill is allowed to do.
+pub fn skill_capabilities(manifest: &SkillManifest) -> Result<Vec<Capability>, SkillError> {
+    manifest
+        .requirements
+        .capabilities
+        .iter()
+        .map(|cap| {
+            cap.parse::<Capability>().map_err(|e| {
+                SkillError::InvalidManifest(format!("skill '{}': {e}", manifest.skill.name))
+            })
+        })
+        .collect()
+}
+
+/// Build the sandbox configuration for one call into a skill.
+pub fn skill_sandbox_config(
+    manifest: &SkillManifest,
+    ssrf_allowed_hosts: Vec<String>,
+) -> Result<SandboxConfig, SkillError> {
+    Ok(SandboxConfig {
+        fuel_limit: SKILL_FUEL_LIMIT,
+        capabilities: skill_capabilities(manifest)?,
+        timeout_secs: Some(SKILL_TIMEOUT_SECS),
+        ssrf_allowed_hosts,
+        ..SandboxConfig::default()
+    })
+}
+
+/// Execute a tool provided by a WASM skill.
+pub async fn execute_wasm_skill(
+    manifest: &SkillManifest,
+    skill_dir: &Path,
+    tool_name: &str,
+    input: &serde_json::Value,
+    kernel: Option<Arc<dyn KernelHandle>>,
+    agent_id: &str,
+    ssrf_allowed_hosts: Vec<String>,
+) -> Result<SkillToolResult, SkillError> {
+    let config = skill_sandbox_config(manifest, ssrf_allowed_hosts)?;
+    run_skill(
+        manifest, skill_dir, tool_name, input, config, kernel, agent_id,
+    )
+    .await
+}
+
+async fn run_skill(
+    manifest: &SkillManifest,
+    skill_dir: &Path,
+    tool_name: &str,
+    input: &serde_json::Value,
+    config: SandboxConfig,
+    kernel: Option<Arc<dyn KernelHandle>>,
+    agent_id: &str,
+) -> Result<SkillToolResult, SkillError> {
+    if !manifest.tools.provided.iter().any(|t| t.name == tool_name) {
+        return Err(SkillError::NotFound(format!(
+            "Tool {tool_name} not in skill manifest"
+        )));
+    }
+
+    // SECURITY: the module must live inside the skill directory.
+    let dir = skill_dir.canonicalize()?;
+    let module_path = dir
+        .join(&manifest.runtime.entry)
+        .canonicalize()
+        .map_err(|e| {
+            SkillError::ExecutionFailed(format!(
+                "WASM module not found: {}: {e}",
+                skill_dir.join(&manifest.runtime.entry).display()
+            ))
+        })?;
+    if !module_path.starts_with(&dir) || !module_path.is_file() {
+        return Err(SkillError::InvalidManifest(format!(
+            "WASM entry '{}' must be a file inside the skill directory",
+            manifest.runtime.entry
+        )));
+    }
+    let wasm_bytes = tokio::fs::read(&module_path).await?;
+
+    debug!(
+        skill = %manifest.skill.name,
+        tool = tool_name,
+        path = %module_path.display(),
+        "Executing WASM skill"
+    );
+
+    let payload = serde_json::json!({
+        "tool": tool_name,
+        "input": input,
+    });
+    let result = shared_sandbox()?
+        .execute(&wasm_bytes, payload, config, kernel, agent_id)
+        .await
+        .map_err(|e| SkillError::ExecutionFailed(e.to_string()))?;
+
+    debug!(
+        skill = %manifest.skill.name,
+        fuel_consumed = result.fuel_consumed,
+        "WASM skill execution complete"
+    );
+
+    let is_error = result.output.get("error").is_some();
+    Ok(SkillToolResult {
+        output: result.output,
+        is_error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Echo skill: returns its `{"tool", "input"}` payload unchanged.
+    const ECHO_WAT: &str = r#"
+        (module
+            (memory (export "memory") 1)
+            (global $bump (mut i32) (i32.const 1024))
+            (func (export "alloc") (param $size i32) (result i32)
+                (local $ptr i32)
+                (local.set $ptr (global.get $bump))
+                (global.set $bump (i32.add (global.get $bump) (local.get $size)))
+                (local.get $ptr))
+            (func (export "execute") (param $ptr i32) (param $len i32) (result i64)
+                (i64.or
+                    (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+                    (i64.extend_i32_u (local.get $len))))
+        )
+    "#;
+
+    /// Skill that calls `fs_read` on `path` through `host_call` and returns
+    /// the host's response.
+    fn fs_read_wat(path: &str) -> String {
+        let request = serde_json::json!({"method": "fs_read", "params": {"path": path}});
+        let request = request.to_string();
+        format!(
+            r#"
+            (module
+                (import "openfang" "host_call" (func $host_call (param i32 i32) (result i64)))
+                (memory (export "memory") 2)
+                (data (i32.const 0) "{}")
+                (global $bump (mut i32) (i32.const 4096))
+                (func (export "alloc") (param $size i32) (result i32)
+                    (local $ptr i32)
+                    (local.set $ptr (global.get $bump))
+                    (global.set $bump (i32.add (global.get $bump) (local.get $size)))
+                    (local.get $ptr))
+                (func (export "execute") (param i32 i32) (result i64)
+                    (call $host_call (i32.const 0) (i32.const {})))
+            )
+            "#,
+            request.replace('\\', "\\\\").replace('"', "\\22"),
+            request.len()
+        )
+    }
+
+    const LOOP_WAT: &str = r#"
+        (module
+            (memory (export "memory") 1)
+            (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+            (func (export "execute") (param i32 i32) (result i64)
+                (loop $inf (br $inf))
+                (i64.const 0))
+        )
+    "#;
+
+    fn skill(dir: &Path, wat: &str, capabilities: &[&str]) -> SkillManifest {
+        std::fs::write(dir.join("skill.wat"), wat).unwrap();
+        serde_json::from_value(serde_json::json!({
+            "skill": { "name": "wasm-test" },
+            "runtime": { "type": "wasm", "entry": "skill.wat" },
+            "tools": { "provided": [{
+                "name": "probe",
+                "description": "test tool",
+                "input_schema": { "type": "object" },
+            }]},
+            "requirements": { "capabilities": capabilities },
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_wasm_skill_receives_tool_payload() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = skill(dir.path(), ECHO_WAT, &[]);
+        let input = serde_json::json!({"city": "Lisbon"});
+
+        let result = execute_wasm_skill(
+            &manifest,
+            dir.path(),
+            "probe",
+            &input,
+            None,
+            "agent",
+            vec![],
+        )
+        .await
+        .unwrap();
+        assert!(!result.is_error);
+        assert_eq!(result.output["tool"], "probe");
+        assert_eq!(result.output["input"], input);
+
+        let err = execute_wasm_skill(
+            &manifest,
+            dir.path(),
+            "missing",
+            &input,
+            None,
+            "agent",
+            vec![],
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, SkillError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_wasm_skill_capabilities_come_from_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.txt");
+        std::fs::write(&notes, "hello").unwrap();
+        let wat = fs_read_wat(&notes.to_string_lossy());
+        let input = serde_json::json!({});
+
+        let denied = skill(dir.path(), &wat, &[]);
+        let result =
+            execute_wasm_skill(&denied, dir.path(), "probe", &input, None, "agent", vec![])
+                .await
+                .unwrap();
+        assert!(result.is_error);
+        assert!(result.output["error"].as_str().unwrap().contains("denied"));
+
+        let granted = skill(dir.path(), &wat, &["FileRead(*)"]);
+        let result =
+            execute_wasm_skill(&granted, dir.path(), "probe", &input, None, "agent", vec![])
+                .await
+                .unwrap();
+        assert!(!result.is_error, "unexpected error: {}", result.output);
+        assert_eq!(result.output["ok"], "hello");
+
+        let typo = skill(dir.path(), &wat, &["FileReed(*)"]);
+        let err = execute_wasm_skill(&typo, dir.path(), "probe", &input, None, "agent", vec![])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SkillError::InvalidManifest(_)));
+    }
+
+    #[tokio::test]
+    async fn test_wasm_skill_is_fuel_limited() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = skill(dir.path(), LOOP_WAT, &[]);
+        let config = SandboxConfig {
+            fuel_limit: 100_000,
+            ..skill_sandbox_config(&manifest, vec![]).unwrap()
+        };
+        let input = serde_json::json!({});
+        let err = run_skill(
+            &manifest,
+            dir.path(),
+            "probe",
+            &input,
+            config,
+            None,
+            "agent",
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("Fuel exhausted"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_wasm_skill_module_must_stay_in_skill_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let skill_dir = root.path().join("skill");
+        std::fs::create_dir(&skill_dir).unwrap();
+        std::fs::write(root.path().join("outside.wat"), ECHO_WAT).unwrap();
+        let mut manifest = skill(&skill_dir, ECHO_WAT, &[]);
+        manifest.runtime.entry = "../outside.wat".to_string();
+
+        let input = serde_json::json!({});
+        let err = execute_wasm_skill(
+            &manifest,
+            &skill_dir,
+            "probe",
+            &input,
+            None,
+            "agent",
+            vec![],
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, SkillError::InvalidManifest(_)), "{err}");
+    }
+}
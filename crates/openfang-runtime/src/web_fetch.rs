@@ -37,6 +37,11 @@ impl WebFetchEngine {
         }
     }
 
+    /// Hosts allowed to bypass SSRF private-IP checks.
+    pub fn ssrf_allowed_hosts(&self) -> &[String] {
+        &self.config.ssrf_allowed_hosts
+    }
+
     /// Fetch a URL with full security pipeline (GET only, for backwards compat).
     pub async fn fetch(&self, url: &str) -> Result<String, String> {
         self.fetch_with_options(url, "GET", None, None).await
@@ -32,7 +32,8 @@ pub async fn execute_skill_tool(
             execute_shell(skill_dir, &manifest.runtime.entry, tool_name, input).await
         }
         SkillRuntime::Wasm => Err(SkillError::RuntimeNotAvailable(
-            "WASM skill runtime not yet implemented".to_string(),
+            "WASM skills run in the agent runtime's sandbox (openfang_runtime::wasm_skill)"
+                .to_string(),
         )),
         SkillRuntime::Builtin => Err(SkillError::RuntimeNotAvailable(
             "Builtin skills are handled by the kernel directly".to_string(),
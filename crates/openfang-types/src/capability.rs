@@ -97,6 +97,70 @@ impl CapabilityCheck {
     }
 }
 
+impl std::str::FromStr for Capability {
+    type Err = String;
+
+    /// Parse the `Variant(arg)` / `Variant` notation used in skill manifests,
+    /// e.g. `NetConnect(api.github.com:443)`, `MemoryRead(*)`, `AgentSpawn`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, arg) = match s.split_once('(') {
+            Some((name, rest)) => {
+                let arg = rest
+                    .strip_suffix(')')
+                    .ok_or_else(|| format!("Unclosed parenthesis in capability '{s}'"))?;
+                (name.trim(), Some(arg.trim()))
+            }
+            None => (s, None),
+        };
+        let text = |arg: Option<&str>| {
+            arg.filter(|a| !a.is_empty())
+                .map(str::to_string)
+                .ok_or_else(|| format!("Capability '{name}' requires an argument"))
+        };
+        let number = |arg: Option<&str>| {
+            text(arg).and_then(|a| {
+                a.parse::<f64>()
+                    .map_err(|_| format!("Capability '{name}' requires a number, got '{a}'"))
+            })
+        };
+        let flag = |cap: Capability| match arg {
+            None | Some("") => Ok(cap),
+            Some(_) => Err(format!("Capability '{name}' takes no argument")),
+        };
+        match name {
+            "FileRead" => text(arg).map(Capability::FileRead),
+            "FileWrite" => text(arg).map(Capability::FileWrite),
+            "NetConnect" => text(arg).map(Capability::NetConnect),
+            "NetListen" => text(arg)?
+                .parse()
+                .map(Capability::NetListen)
+                .map_err(|_| format!("Capability '{name}' requires a port number")),
+            "ToolInvoke" => text(arg).map(Capability::ToolInvoke),
+            "ToolAll" => flag(Capability::ToolAll),
+            "LlmQuery" => text(arg).map(Capability::LlmQuery),
+            "LlmMaxTokens" => text(arg)?
+                .parse()
+                .map(Capability::LlmMaxTokens)
+                .map_err(|_| format!("Capability '{name}' requires a token count")),
+            "AgentSpawn" => flag(Capability::AgentSpawn),
+            "AgentMessage" => text(arg).map(Capability::AgentMessage),
+            "AgentKill" => text(arg).map(Capability::AgentKill),
+            "MemoryRead" => text(arg).map(Capability::MemoryRead),
+            "MemoryWrite" => text(arg).map(Capability::MemoryWrite),
+            "ShellExec" => text(arg).map(Capability::ShellExec),
+            "EnvRead" => text(arg).map(Capability::EnvRead),
+            "OfpDiscover" => flag(Capability::OfpDiscover),
+            "OfpConnect" => text(arg).map(Capability::OfpConnect),
+            "OfpAdvertise" => flag(Capability::OfpAdvertise),
+            "EconSpend" => number(arg).map(Capability::EconSpend),
+            "EconEarn" => flag(Capability::EconEarn),
+            "EconTransfer" => text(arg).map(Capability::EconTransfer),
+            _ => Err(format!("Unknown capability '{name}'")),
+        }
+    }
+}
+
 /// Checks whether a required capability matches any granted capability.
 ///
 /// Pattern matching rules:
@@ -215,6 +279,35 @@ fn glob_matches(pattern: &str, value: &str) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_capability_strings() {
+        assert_eq!(
+            "NetConnect(api.github.com:443)".parse::<Capability>(),
+            Ok(Capability::NetConnect("api.github.com:443".to_string()))
+        );
+        assert_eq!(
+            " MemoryRead( * ) ".parse::<Capability>(),
+            Ok(Capability::MemoryRead("*".to_string()))
+        );
+        assert_eq!(
+            "AgentSpawn".parse::<Capability>(),
+            Ok(Capability::AgentSpawn)
+        );
+        assert_eq!(
+            "NetListen(8080)".parse::<Capability>(),
+            Ok(Capability::NetListen(8080))
+        );
+        assert_eq!(
+            "EconSpend(2.5)".parse::<Capability>(),
+            Ok(Capability::EconSpend(2.5))
+        );
+        assert!("NetConnect".parse::<Capability>().is_err());
+        assert!("NetConnect(*".parse::<Capability>().is_err());
+        assert!("AgentSpawn(yes)".parse::<Capability>().is_err());
+        assert!("NetListen(http)".parse::<Capability>().is_err());
+        assert!("RootAccess".parse::<Capability>().is_err());
+    }
+
     #[test]
     fn test_exact_match() {
         assert!(capability_matches(
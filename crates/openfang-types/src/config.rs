@@ -1647,12 +1647,43 @@ pub struct MemoryConfig {
     /// Env var name holding the HTTP memory API bearer token.
     #[serde(default)]
     pub http_token_env: Option<String>,
+    /// Cosine similarity at or above which two embedded memories are merged
+    /// during consolidation.
+    #[serde(default = "default_consolidation_similarity")]
+    pub consolidation_similarity: f32,
+    /// Word-overlap (Jaccard) similarity used instead when a memory has no embedding.
+    #[serde(default = "default_consolidation_text_similarity")]
+    pub consolidation_text_similarity: f32,
+    /// Ask the default LLM to write the merged text for each cluster
+    /// instead of keeping the highest-confidence member verbatim.
+    #[serde(default)]
+    pub consolidation_llm_summary: bool,
+    /// Keep an HNSW vector index (persisted next to the SQLite file) so
+    /// semantic recall searches all memories, not just recently used ones.
+    #[serde(default = "default_true")]
+    pub vector_index: bool,
+    /// Weight of the BM25 keyword score when ranking semantic recall
+    /// results (0.0 = vector similarity only, 1.0 = keywords only).
+    #[serde(default = "default_hybrid_keyword_weight")]
+    pub hybrid_keyword_weight: f32,
 }
 
 fn default_consolidation_interval() -> u64 {
     24
 }
 
+fn default_consolidation_similarity() -> f32 {
+    0.92
+}
+
+fn default_consolidation_text_similarity() -> f32 {
+    0.85
+}
+
+fn default_hybrid_keyword_weight() -> f32 {
+    0.3
+}
+
 fn default_memory_backend() -> String {
     "sqlite".to_string()
 }
@@ -1670,6 +1701,11 @@ impl Default for MemoryConfig {
             backend: default_memory_backend(),
             http_url: None,
             http_token_env: None,
+            consolidation_similarity: default_consolidation_similarity(),
+            consolidation_text_similarity: default_consolidation_text_similarity(),
+            consolidation_llm_summary: false,
+            vector_index: true,
+            hybrid_keyword_weight: default_hybrid_keyword_weight(),
         }
     }
 }
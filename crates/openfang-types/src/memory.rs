@@ -90,8 +90,56 @@ pub struct MemoryFilter {
     pub after: Option<DateTime<Utc>>,
     /// Only memories created before this time.
     pub before: Option<DateTime<Utc>>,
-    /// Metadata key-value filters.
+    /// Metadata key-value filters (each key must equal its value).
     pub metadata: HashMap<String, serde_json::Value>,
+    /// Additional metadata predicates (existence, `IN`), ANDed with `metadata`.
+    #[serde(default)]
+    pub metadata_predicates: Vec<MetadataPredicate>,
+}
+
+/// A predicate on a top-level key of a memory's JSON metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum MetadataPredicate {
+    /// `metadata[key]` equals `value`.
+    Equals {
+        /// Metadata key.
+        key: String,
+        /// Expected value.
+        value: serde_json::Value,
+    },
+    /// `metadata[key]` is present (`exists = true`) or absent (`exists = false`).
+    Exists {
+        /// Metadata key.
+        key: String,
+        /// Whether the key must be present.
+        exists: bool,
+    },
+    /// `metadata[key]` equals one of `values`.
+    In {
+        /// Metadata key.
+        key: String,
+        /// Accepted values.
+        values: Vec<serde_json::Value>,
+    },
+}
+
+impl MetadataPredicate {
+    /// The metadata key this predicate inspects.
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Equals { key, .. } | Self::Exists { key, .. } | Self::In { key, .. } => key,
+        }
+    }
+
+    /// Evaluate the predicate against a metadata map.
+    pub fn matches(&self, metadata: &HashMap<String, serde_json::Value>) -> bool {
+        match self {
+            Self::Equals { key, value } => metadata.get(key) == Some(value),
+            Self::Exists { key, exists } => metadata.contains_key(key) == *exists,
+            Self::In { key, values } => metadata.get(key).is_some_and(|v| values.contains(v)),
+        }
+    }
 }
 
 impl MemoryFilter {
@@ -110,6 +158,36 @@ impl MemoryFilter {
             ..Default::default()
         }
     }
+
+    /// All metadata predicates: the `metadata` equality map followed by
+    /// `metadata_predicates`.
+    pub fn all_metadata_predicates(&self) -> Vec<MetadataPredicate> {
+        let mut keys: Vec<&String> = self.metadata.keys().collect();
+        keys.sort();
+        keys.into_iter()
+            .map(|k| MetadataPredicate::Equals {
+                key: k.clone(),
+                value: self.metadata[k].clone(),
+            })
+            .chain(self.metadata_predicates.iter().cloned())
+            .collect()
+    }
+
+    /// Whether a fragment's creation time and metadata satisfy this filter's
+    /// time bounds and metadata predicates. Used where filters can't be
+    /// pushed into SQL.
+    pub fn matches_time_and_metadata(
+        &self,
+        created_at: DateTime<Utc>,
+        metadata: &HashMap<String, serde_json::Value>,
+    ) -> bool {
+        self.after.is_none_or(|t| created_at > t)
+            && self.before.is_none_or(|t| created_at < t)
+            && self
+                .all_metadata_predicates()
+                .iter()
+                .all(|p| p.matches(metadata))
+    }
 }
 
 /// An entity in the knowledge graph.
@@ -199,16 +277,48 @@ pub enum RelationType {
 }
 
 /// A pattern for querying the knowledge graph.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Matches directed paths of 1 to `max_depth` hops that start at `source`
+/// and end at `target` (either may be omitted). A path never visits the
+/// same entity twice.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GraphPattern {
     /// Optional source entity filter.
     pub source: Option<String>,
-    /// Optional relation type filter.
+    /// Optional relation type filter, applied to every hop without a
+    /// per-hop override in `hops`.
     pub relation: Option<RelationType>,
     /// Optional target entity filter.
     pub target: Option<String>,
     /// Maximum traversal depth.
     pub max_depth: u32,
+    /// Optional type filter for every entity reached by a hop (everything
+    /// except the path's starting entity), unless overridden in `hops`.
+    #[serde(default)]
+    pub entity_type: Option<EntityType>,
+    /// Per-hop filters: `hops[0]` constrains the first hop, and so on.
+    #[serde(default)]
+    pub hops: Vec<HopFilter>,
+    /// Relations below this confidence are not traversed.
+    #[serde(default)]
+    pub min_confidence: f32,
+    /// Maximum number of results (0 = default of 100).
+    #[serde(default)]
+    pub limit: usize,
+    /// Number of results to skip, for pagination.
+    #[serde(default)]
+    pub offset: usize,
+}
+
+/// Constraints on a single hop of a graph traversal.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HopFilter {
+    /// Relation type this hop must follow.
+    #[serde(default)]
+    pub relation: Option<RelationType>,
+    /// Type of the entity this hop must reach.
+    #[serde(default)]
+    pub entity_type: Option<EntityType>,
 }
 
 /// A result from a graph query.
@@ -222,6 +332,44 @@ pub struct GraphMatch {
     pub target: Entity,
 }
 
+/// A path through the knowledge graph.
+///
+/// `entities` has one more element than `relations`; `relations[i]` connects
+/// `entities[i]` and `entities[i + 1]`. Relations keep their stored direction,
+/// so an undirected path may contain relations that point backwards.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphPath {
+    /// Entities along the path, starting entity first.
+    pub entities: Vec<Entity>,
+    /// Relations between consecutive entities.
+    pub relations: Vec<Relation>,
+    /// Product of the relation confidences along the path.
+    pub confidence: f32,
+}
+
+impl GraphPath {
+    /// Number of hops in the path.
+    pub fn len(&self) -> usize {
+        self.relations.len()
+    }
+
+    /// Whether the path has no hops.
+    pub fn is_empty(&self) -> bool {
+        self.relations.is_empty()
+    }
+}
+
+/// Entities and relations within a number of hops of a center entity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNeighborhood {
+    /// The entity the neighborhood was expanded from.
+    pub center: Entity,
+    /// Reachable entities with their hop distance from the center.
+    pub entities: Vec<(Entity, u32)>,
+    /// Relations between entities in the neighborhood.
+    pub relations: Vec<Relation>,
+}
+
 /// Report from memory consolidation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsolidationReport {
@@ -242,8 +390,57 @@ pub enum ExportFormat {
     MessagePack,
 }
 
+impl ExportFormat {
+    /// Parse a format name (`json`, `msgpack` / `messagepack`), case-insensitive.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "msgpack" | "messagepack" | "mp" => Some(Self::MessagePack),
+            _ => None,
+        }
+    }
+
+    /// MIME type for the serialized payload.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::MessagePack => "application/msgpack",
+        }
+    }
+
+    /// Conventional file extension (without the dot).
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::MessagePack => "msgpack",
+        }
+    }
+}
+
+/// How imported records interact with data already in the store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Upsert records by ID, keeping everything not present in the import.
+    #[default]
+    Merge,
+    /// Clear the covered data first, then insert the imported records.
+    Replace,
+}
+
+impl ImportMode {
+    /// Parse a mode name (`merge` or `replace`), case-insensitive.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "merge" => Some(Self::Merge),
+            "replace" => Some(Self::Replace),
+            _ => None,
+        }
+    }
+}
+
 /// Report from memory import.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ImportReport {
     /// Number of entities imported.
     pub entities_imported: u64,
@@ -251,6 +448,12 @@ pub struct ImportReport {
     pub relations_imported: u64,
     /// Number of memories imported.
     pub memories_imported: u64,
+    /// Number of key-value pairs imported.
+    #[serde(default)]
+    pub kv_imported: u64,
+    /// Number of sessions (regular and canonical) imported.
+    #[serde(default)]
+    pub sessions_imported: u64,
     /// Errors encountered during import.
     pub errors: Vec<String>,
 }
@@ -346,6 +549,57 @@ mod tests {
         assert!(filter.source.is_none());
     }
 
+    #[test]
+    fn test_memory_filter_metadata_predicates() {
+        let mut meta = HashMap::new();
+        meta.insert("topic".to_string(), serde_json::json!("rust"));
+        meta.insert("priority".to_string(), serde_json::json!(2));
+
+        let mut filter = MemoryFilter::default();
+        filter
+            .metadata
+            .insert("topic".to_string(), serde_json::json!("rust"));
+        filter.metadata_predicates = vec![
+            MetadataPredicate::In {
+                key: "priority".to_string(),
+                values: vec![serde_json::json!(1), serde_json::json!(2)],
+            },
+            MetadataPredicate::Exists {
+                key: "archived".to_string(),
+                exists: false,
+            },
+        ];
+        let now = Utc::now();
+        assert!(filter.matches_time_and_metadata(now, &meta));
+
+        filter.after = Some(now);
+        assert!(!filter.matches_time_and_metadata(now, &meta));
+        filter.after = None;
+
+        meta.insert("archived".to_string(), serde_json::json!(true));
+        assert!(!filter.matches_time_and_metadata(now, &meta));
+
+        let json = r#"{"op":"in","key":"priority","values":[1,2]}"#;
+        let p: MetadataPredicate = serde_json::from_str(json).unwrap();
+        assert_eq!(p.key(), "priority");
+    }
+
+    #[test]
+    fn test_export_format_and_import_mode_parse() {
+        assert!(matches!(
+            ExportFormat::parse("JSON"),
+            Some(ExportFormat::Json)
+        ));
+        assert!(matches!(
+            ExportFormat::parse("msgpack"),
+            Some(ExportFormat::MessagePack)
+        ));
+        assert!(ExportFormat::parse("xml").is_none());
+        assert_eq!(ImportMode::parse("Replace"), Some(ImportMode::Replace));
+        assert_eq!(ImportMode::default(), ImportMode::Merge);
+        assert!(ImportMode::parse("append").is_none());
+    }
+
     #[test]
     fn test_memory_fragment_serialization() {
         let fragment = MemoryFragment {